@@ -1,18 +1,27 @@
+#[path = "src/data/buff_def.rs"]
+mod buff_def;
+
 #[path = "src/data/skill_def.rs"]
 mod skill_def;
 
+use buff_def::BuffDef;
+use evtc::Profession;
 use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
+use serde::Serialize;
 use skill_def::SkillDef;
 use std::{
+    collections::HashMap,
     env,
+    ffi::OsStr,
     fs::{self, File},
-    path::PathBuf,
+    iter,
+    path::{Path, PathBuf},
 };
 use winresource::WindowsResource;
 
 fn main() {
-    let manifest = env::var_os("CARGO_MANIFEST_DIR").unwrap();
+    let manifest = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
     let out_dir = env::var_os("OUT_DIR").unwrap();
     let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
 
@@ -22,8 +31,10 @@ fn main() {
         }
     }
 
-    let in_dir = PathBuf::from(manifest).join("src/data/skills");
-    let files = fs::read_dir(in_dir)
+    // directory change detects added and removed files
+    let in_dir = manifest.join("src/data/skills");
+    println!("cargo:rerun-if-changed={}", in_dir.display());
+    let mut files = fs::read_dir(&in_dir)
         .unwrap()
         .map(|entry| entry.unwrap().path())
         .filter(|path| {
@@ -31,43 +42,243 @@ fn main() {
                 path.extension().and_then(|ext| ext.to_str()),
                 Some("json" | "yml" | "yaml")
             )
-        });
+        })
+        .collect::<Vec<_>>();
+    files.sort();
 
-    let data = files.flat_map(|file| {
-        println!("cargo:rerun-if-changes={}", file.display());
-        let file = File::open(file).unwrap();
-        serde_yaml::from_reader::<_, Vec<SkillDef>>(file).unwrap()
-    });
+    let mut data = Vec::new();
+    for path in &files {
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        // profession files restrict their definitions to the profession
+        let profession = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(profession_from_name);
+        let file = File::open(path)
+            .unwrap_or_else(|err| panic!("failed to open \"{}\": {err}", path.display()));
+        let defs = serde_yaml::from_reader::<_, Vec<SkillDef>>(file)
+            .unwrap_or_else(|err| panic!("failed to parse \"{}\": {err}", path.display()));
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        for mut def in defs {
+            def.profession = def.profession.or(profession);
+            data.push(IndexEntry {
+                file: name.clone(),
+                def,
+            });
+        }
+    }
+
+    let errors = validate(&data);
+    if !errors.is_empty() {
+        panic!(
+            "invalid skill definitions:\n{}",
+            errors
+                .iter()
+                .map(|err| format!("  {err}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    let index = File::create(PathBuf::from(&out_dir).join("skills_index.json")).unwrap();
+    serde_json::to_writer_pretty(index, &data).unwrap();
+
+    let data = data.into_iter().map(|entry| entry.def);
 
     let contents = data.map(|skill| {
         let SkillDef {
             id,
+            name,
             enabled,
             hit_ids,
             hits,
             expected,
             max_duration,
             minion,
+            buff_id,
+            buff_cast,
+            buff_hits,
+            profession,
+            spec,
         } = skill;
+        let name = quote_option(name.map(|name| quote! { #name.into() }));
         let hits = quote_option(hits);
         let expected = quote_option(expected);
         let max_duration = quote_option(max_duration);
+        let buff_id = quote_option(buff_id);
+        let profession = quote_option(profession.map(|prof| {
+            let ident = format_ident!("{prof:?}");
+            quote! { ::evtc::Profession::#ident }
+        }));
+        let spec = quote_option(spec.map(|spec| {
+            let ident = format_ident!("{spec:?}");
+            quote! { ::evtc::Specialization::#ident }
+        }));
         quote! {
             SkillDef {
                 id: #id,
+                name: #name,
                 enabled: #enabled,
                 hit_ids: vec![ #(#hit_ids),* ],
                 hits: #hits,
                 expected: #expected,
                 max_duration: #max_duration,
                 minion: #minion,
+                buff_id: #buff_id,
+                buff_cast: #buff_cast,
+                buff_hits: #buff_hits,
+                profession: #profession,
+                spec: #spec,
+            }
+        }
+    });
+
+    let result = quote! { [ #(#contents),* ] };
+
+    fs::write(
+        PathBuf::from(&out_dir).join("skills.rs"),
+        result.to_string(),
+    )
+    .unwrap();
+
+    generate_buffs(&manifest.join("src/data/buffs.yml"), &out_dir);
+}
+
+/// Generates the bundled buff definitions.
+fn generate_buffs(path: &Path, out_dir: &OsStr) {
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let file = File::open(path)
+        .unwrap_or_else(|err| panic!("failed to open \"{}\": {err}", path.display()));
+    let defs = serde_yaml::from_reader::<_, Vec<BuffDef>>(file)
+        .unwrap_or_else(|err| panic!("failed to parse \"{}\": {err}", path.display()));
+
+    let errors = validate_buffs(&defs);
+    if !errors.is_empty() {
+        panic!(
+            "invalid buff definitions:\n{}",
+            errors
+                .iter()
+                .map(|err| format!("  {err}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    let contents = defs.into_iter().map(|buff| {
+        let BuffDef {
+            id,
+            alt_ids,
+            name,
+            enabled,
+            category,
+            stacking,
+            stacks,
+        } = buff;
+        let category = format_ident!("{category:?}");
+        let stacking = format_ident!("{stacking:?}");
+        quote! {
+            BuffDef {
+                id: #id,
+                alt_ids: vec![ #(#alt_ids),* ],
+                name: #name.into(),
+                enabled: #enabled,
+                category: BuffCategory::#category,
+                stacking: StackingType::#stacking,
+                stacks: #stacks,
             }
         }
     });
 
     let result = quote! { [ #(#contents),* ] };
 
-    fs::write(PathBuf::from(out_dir).join("skills.rs"), result.to_string()).unwrap();
+    fs::write(PathBuf::from(out_dir).join("buffs.rs"), result.to_string()).unwrap();
+}
+
+/// Validates the bundled buff definitions, returning a list of errors.
+fn validate_buffs(defs: &[BuffDef]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut ids = HashMap::new();
+
+    for def in defs {
+        let id = def.id;
+        for other_id in iter::once(&def.id).chain(&def.alt_ids) {
+            if let Some(other) = ids.insert(*other_id, id) {
+                errors.push(format!(
+                    "buff id {other_id} of {id} already used by {other}"
+                ));
+            }
+        }
+        if def.stacks == 0 {
+            errors.push(format!("zero stacks of buff {id}"));
+        }
+    }
+
+    errors
+}
+
+/// Bundled skill definition with its source file.
+#[derive(Debug, Serialize)]
+struct IndexEntry {
+    file: String,
+
+    #[serde(flatten)]
+    def: SkillDef,
+}
+
+/// Validates the bundled skill definitions, returning a list of errors.
+fn validate(data: &[IndexEntry]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut ids = HashMap::new();
+    let mut hit_ids = HashMap::new();
+
+    for IndexEntry { file, def } in data {
+        let id = def.id;
+        if let Some(other) = ids.insert(id, file) {
+            errors.push(format!(
+                "{file}: duplicate id {id}, already defined in {other}"
+            ));
+        }
+        for hit_id in &def.hit_ids {
+            if let Some((other_file, other)) = hit_ids.insert(*hit_id, (file, id)) {
+                errors.push(format!(
+                    "{file}: hit id {hit_id} of {id} already used by {other} in {other_file}"
+                ));
+            }
+        }
+        if let (Some(hits), Some(expected)) = (def.hits, def.expected) {
+            if expected > hits {
+                errors.push(format!(
+                    "{file}: expected {expected} of {id} above hits {hits}"
+                ));
+            }
+        }
+        if let Some(max_duration) = def.max_duration {
+            if max_duration <= 0 {
+                errors.push(format!(
+                    "{file}: non-positive max duration {max_duration} of {id}"
+                ));
+            }
+        }
+    }
+
+    // hit ids must not overlap with primary ids
+    for (hit_id, (file, id)) in &hit_ids {
+        if let Some(other_file) = ids.get(hit_id) {
+            errors.push(format!(
+                "{file}: hit id {hit_id} of {id} already defined as skill in {other_file}"
+            ));
+        }
+    }
+
+    errors
+}
+
+fn profession_from_name(name: &str) -> Option<Profession> {
+    (1..=9)
+        .map(Profession::from)
+        .find(|prof| format!("{prof:?}").eq_ignore_ascii_case(name))
 }
 
 fn quote_option(option: Option<impl ToTokens>) -> TokenStream {
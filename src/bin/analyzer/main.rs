@@ -0,0 +1,147 @@
+//! Headless analyzer for recorded ArcDPS logs.
+
+mod report;
+
+use arcdps_buddy::{
+    combat::skill::SkillMap,
+    data::{DataSource, SkillData},
+    history::History,
+    processor::Processor,
+    replay::EvtcFile,
+};
+use report::FightReport;
+use std::{env, io, path::PathBuf, process::ExitCode};
+
+/// Usage information.
+const USAGE: &str = "Usage: buddy_analyzer <log.evtc|log.zevtc> [--skills <skills.yml|dir>] [--source <defaults|custom|merged>] [--json]";
+
+/// Help information.
+const HELP: &str = "Analyzes casts, breakbar hits and transfers of a recorded log
+
+Options:
+  --skills <path>    Custom skill definitions file or directory of definition files
+  --source <source>  Skill definitions to use: defaults, custom or merged (default: merged)
+  --json             Output JSON instead of text
+  -h, --help         Print this help";
+
+fn main() -> ExitCode {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{HELP}\n\n{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut custom = Vec::new();
+    if let Some(path) = &args.skills {
+        let result = if path.is_dir() {
+            SkillData::read_dir(path)
+        } else {
+            SkillData::read_file(path)
+        };
+        match result {
+            Ok(data) => custom = data,
+            Err(err) => {
+                eprintln!("Failed to load custom definitions: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+        for warning in SkillData::check(args.source, &custom) {
+            eprintln!("Warning: {warning}");
+        }
+    }
+    let data = SkillData::from_source(args.source, custom);
+
+    let log = match EvtcFile::from_path(&args.log) {
+        Ok(log) => log,
+        Err(err) => {
+            eprintln!("Failed to read log \"{}\": {err:?}", args.log.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut processor = Processor::new(SkillMap::new(), data, History::new(usize::MAX, 0, false));
+    processor.replay(&log);
+
+    let Processor {
+        skills,
+        data,
+        history,
+        ..
+    } = &mut processor;
+    let reports: Vec<_> = history
+        .all_fights()
+        .rev()
+        .map(|fight| FightReport::new(fight, skills, data))
+        .collect();
+
+    if args.json {
+        if let Err(err) = serde_json::to_writer_pretty(io::stdout().lock(), &reports) {
+            eprintln!("Failed to write output: {err}");
+            return ExitCode::FAILURE;
+        }
+        println!();
+    } else {
+        for report in &reports {
+            println!("{report}");
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Command line arguments.
+#[derive(Debug)]
+struct Args {
+    /// Path to the log file.
+    log: PathBuf,
+
+    /// Path to custom skill definitions.
+    skills: Option<PathBuf>,
+
+    /// Source of skill definitions.
+    source: DataSource,
+
+    /// Whether to output JSON.
+    json: bool,
+}
+
+impl Args {
+    /// Parses the command line arguments.
+    ///
+    /// Returns `None` if help was requested.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut log = None;
+        let mut skills = None;
+        let mut source = DataSource::default();
+        let mut json = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => json = true,
+                "--skills" => skills = Some(args.next().ok_or("Missing path for --skills")?.into()),
+                "--source" => {
+                    let value = args.next().ok_or("Missing value for --source")?;
+                    source = value
+                        .parse()
+                        .map_err(|_| format!("Unknown source \"{value}\""))?;
+                }
+                "-h" | "--help" => return Ok(None),
+                _ if arg.starts_with('-') => return Err(format!("Unknown option \"{arg}\"")),
+                _ => log = Some(arg.into()),
+            }
+        }
+
+        Ok(Some(Self {
+            log: log.ok_or("Missing log path")?,
+            skills,
+            source,
+            json,
+        }))
+    }
+}
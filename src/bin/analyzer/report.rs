@@ -0,0 +1,327 @@
+use arcdps_buddy::{
+    combat::{cast::Cast, skill::SkillMap, summary::SkillSummary, CombatData},
+    data::{SkillData, SkillHits},
+    history::Fight,
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
+
+/// Evaluation of a single fight.
+#[derive(Debug, Clone, Serialize)]
+pub struct FightReport {
+    /// Target or fight name.
+    pub name: String,
+
+    /// Fight target species.
+    pub target: Option<u32>,
+
+    /// Fight duration (ms).
+    pub duration: Option<u64>,
+
+    /// Casts of defined skills.
+    pub casts: Vec<CastReport>,
+
+    /// Damage per defined skill.
+    pub skills: Vec<SkillReport>,
+
+    /// Defiance damage hits.
+    pub breakbar: Vec<BreakbarReport>,
+
+    /// Condition transfers.
+    pub transfers: Vec<TransferReport>,
+}
+
+impl FightReport {
+    /// Creates a new report for the fight.
+    pub fn new(fight: &Fight<CombatData>, skills: &mut SkillMap, data: &SkillData) -> Self {
+        let casts = fight
+            .data
+            .casts
+            .iter()
+            .filter_map(|cast| {
+                data.get(cast.skill).map(|info| CastReport {
+                    time: cast.time,
+                    skill: cast.skill,
+                    name: skills.get_name(cast.skill).into(),
+                    state: cast.state.as_ref().into(),
+                    duration: cast.duration,
+                    damage: cast.damage(),
+                    hits: info
+                        .hits
+                        .as_ref()
+                        .map(|hit_info| HitReport::new(cast, fight.target, hit_info)),
+                })
+            })
+            .collect();
+
+        let summaries = SkillSummary::from_casts(&fight.data.casts)
+            .into_iter()
+            .map(|summary| SkillReport {
+                skill: summary.skill,
+                name: skills.get_name(summary.skill).into(),
+                casts: summary.casts,
+                damage: summary.damage,
+                average_damage: summary.average_damage(),
+                damage_per_second: summary.damage_per_second(),
+            })
+            .collect();
+
+        let breakbar = fight
+            .data
+            .breakbar
+            .iter()
+            .map(|hit| BreakbarReport {
+                time: hit.time,
+                skill: hit.skill,
+                name: skills.get_name(hit.skill).into(),
+                damage: hit.damage as f32 / 10.0,
+                attacker: hit.attacker.name.clone(),
+                is_own: hit.is_own,
+                target: hit.target.name.clone(),
+            })
+            .collect();
+
+        let transfers = fight
+            .data
+            .transfers
+            .found()
+            .iter()
+            .map(|transfer| TransferReport {
+                time: transfer.time,
+                condition: transfer.condi.as_ref().into(),
+                stacks: transfer.stacks,
+                target: transfer.target.name.clone(),
+            })
+            .collect();
+
+        Self {
+            name: fight.name.clone().unwrap_or_else(|| "Unknown".into()),
+            target: fight.target,
+            duration: fight.duration(),
+            casts,
+            skills: summaries,
+            breakbar,
+            transfers,
+        }
+    }
+}
+
+impl fmt::Display for FightReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.duration {
+            Some(duration) => writeln!(f, "{} ({}s)", self.name, duration / 1000)?,
+            None => writeln!(f, "{} (?s)", self.name)?,
+        }
+
+        writeln!(f, "Casts:")?;
+        for cast in &self.casts {
+            write!(f, "  {} {}", format_time(cast.time), cast.name)?;
+            if let Some(hits) = &cast.hits {
+                write!(
+                    f,
+                    " {} {} ({} {})",
+                    hits.format(hits.target),
+                    hits.target_category,
+                    hits.format(hits.cleave),
+                    hits.cleave_category
+                )?;
+                if !hits.failed.is_empty() {
+                    let failed: Vec<_> = hits
+                        .failed
+                        .iter()
+                        .map(|(outcome, count)| format!("{count} {outcome}"))
+                        .collect();
+                    write!(f, " [{}]", failed.join(", "))?;
+                }
+            }
+            writeln!(f, " {} dmg {}ms {}", cast.damage, cast.duration, cast.state)?;
+        }
+
+        writeln!(f, "Skills:")?;
+        for skill in &self.skills {
+            write!(
+                f,
+                "  {} {} casts {} dmg {:.0} avg",
+                skill.name, skill.casts, skill.damage, skill.average_damage
+            )?;
+            match skill.damage_per_second {
+                Some(dps) => writeln!(f, " {dps:.0} dps")?,
+                None => writeln!(f, " ? dps")?,
+            }
+        }
+
+        writeln!(f, "Breakbar:")?;
+        for hit in &self.breakbar {
+            writeln!(
+                f,
+                "  {} {:.1} {} {} -> {}",
+                format_time(hit.time),
+                hit.damage,
+                hit.name,
+                hit.attacker,
+                hit.target
+            )?;
+        }
+
+        writeln!(f, "Transfers:")?;
+        for transfer in &self.transfers {
+            writeln!(
+                f,
+                "  {} {} {} -> {}",
+                format_time(transfer.time),
+                transfer.stacks,
+                transfer.condition,
+                transfer.target
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Evaluation of a single cast.
+#[derive(Debug, Clone, Serialize)]
+pub struct CastReport {
+    /// Time of the cast.
+    pub time: i32,
+
+    /// Casted skill id.
+    pub skill: u32,
+
+    /// Casted skill name.
+    pub name: String,
+
+    /// Final cast state.
+    pub state: String,
+
+    /// Time spent in animation.
+    pub duration: i32,
+
+    /// Damage dealt by the hits.
+    pub damage: i64,
+
+    /// Hit evaluation, if the skill tracks hits.
+    pub hits: Option<HitReport>,
+}
+
+/// Damage summary of a single skill.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillReport {
+    /// Skill id.
+    pub skill: u32,
+
+    /// Skill name.
+    pub name: String,
+
+    /// Number of casts.
+    pub casts: usize,
+
+    /// Total damage dealt.
+    pub damage: i64,
+
+    /// Average damage per cast.
+    pub average_damage: f64,
+
+    /// Damage per second of animation time.
+    pub damage_per_second: Option<f64>,
+}
+
+/// Evaluation of the hits of a cast.
+#[derive(Debug, Clone, Serialize)]
+pub struct HitReport {
+    /// Total amount of hits, if known.
+    pub max: Option<usize>,
+
+    /// Minimum amount of hits expected.
+    pub expected: usize,
+
+    /// Hits on the fight target.
+    pub target: usize,
+
+    /// Category of hits on the fight target.
+    pub target_category: String,
+
+    /// Hits on all targets.
+    pub cleave: usize,
+
+    /// Category of hits on all targets.
+    pub cleave_category: String,
+
+    /// Failed hit attempts per outcome.
+    pub failed: BTreeMap<String, usize>,
+}
+
+impl HitReport {
+    /// Creates a new hit report for the cast.
+    fn new(cast: &Cast, species: Option<u32>, info: &SkillHits) -> Self {
+        let target = cast.target_hits(species);
+        let cleave = cast.hit_count();
+        Self {
+            max: info.has_hits().then_some(info.max),
+            expected: info.expected,
+            target,
+            target_category: info.categorize(target).as_ref().into(),
+            cleave,
+            cleave_category: info.categorize(cleave).as_ref().into(),
+            failed: cast
+                .failed_hits()
+                .into_iter()
+                .map(|(outcome, count)| (outcome.as_ref().into(), count))
+                .collect(),
+        }
+    }
+
+    /// Formats a hit count.
+    fn format(&self, hits: usize) -> String {
+        match self.max {
+            Some(max) => format!("{hits}/{max}"),
+            None => format!("{hits}/X"),
+        }
+    }
+}
+
+/// Evaluation of a defiance damage hit.
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakbarReport {
+    /// Time of the hit.
+    pub time: i32,
+
+    /// Skill id causing the hit.
+    pub skill: u32,
+
+    /// Skill name causing the hit.
+    pub name: String,
+
+    /// Defiance damage dealt.
+    pub damage: f32,
+
+    /// Name of the attacker.
+    pub attacker: String,
+
+    /// Whether the attacker is the recording player.
+    pub is_own: bool,
+
+    /// Name of the target.
+    pub target: String,
+}
+
+/// Evaluation of a condition transfer.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferReport {
+    /// Time of the transfer.
+    pub time: i32,
+
+    /// Condition transferred.
+    pub condition: String,
+
+    /// Amount of stacks transferred.
+    pub stacks: u32,
+
+    /// Name of the target.
+    pub target: String,
+}
+
+/// Formats a relative time.
+fn format_time(time: i32) -> String {
+    format!("{:>3}.{:03}", time / 1000, time.abs() % 1000)
+}
@@ -1,17 +1,17 @@
 use super::{name_of, Player};
-use arc_util::colors::{with_alpha, GREEN, GREY, RED, YELLOW};
-use arcdps::{
-    evtc::{self, AgentKind},
-    exports::{Colors, CoreColor},
-    Profession,
-};
+use evtc::{AgentKind, Profession};
 use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, EnumIter, VariantArray, VariantNames};
 
+#[cfg(feature = "plugin")]
+use arc_util::colors::{with_alpha, GREEN, GREY, RED, YELLOW};
+#[cfg(feature = "plugin")]
+use arcdps::exports::{Colors, CoreColor};
+
 // TODO: show id settings?
 
 /// Information about an agent.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Agent {
     /// Kind of agent.
     pub kind: AgentKind,
@@ -45,7 +45,10 @@ impl Agent {
     pub fn is_player(&self) -> bool {
         matches!(self.kind, AgentKind::Player)
     }
+}
 
+#[cfg(feature = "plugin")]
+impl Agent {
     /// Returns agent profession color.
     pub fn prof_color(&self, colors: &Colors) -> [f32; 4] {
         colors.prof_base(self.profession).unwrap_or(GREY)
@@ -1,7 +1,8 @@
 use super::agent::Agent;
+use serde::{Deserialize, Serialize};
 
 /// Information about a defiance damage hit.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BreakbarHit {
     /// Time of the hit.
     pub time: i32,
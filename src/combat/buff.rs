@@ -1,9 +1,11 @@
 use super::agent::Agent;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, mem};
 
-pub use crate::data::Buff;
+pub use crate::data::{Buff, Stacking};
 
 /// Information about a buff application.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuffApply {
     /// Time of the application.
     pub time: i32,
@@ -14,18 +16,457 @@ pub struct BuffApply {
     /// Duration of buff applied.
     pub duration: i32,
 
+    /// Duration actually added, excluding overstack & overwritten stacks.
+    pub effective: i32,
+
     /// Target the buff was applied to.
     pub target: Agent,
 }
 
 impl BuffApply {
     /// Creates a new buff apply.
-    pub fn new(time: i32, buff: Buff, duration: i32, target: Agent) -> Self {
+    pub fn new(time: i32, buff: Buff, duration: i32, effective: i32, target: Agent) -> Self {
         Self {
             buff,
             time,
             duration,
+            effective,
             target,
         }
     }
+
+    /// Returns the duration lost to overstack or overwritten stacks.
+    pub fn wasted(&self) -> i32 {
+        self.duration - self.effective
+    }
+}
+
+/// Simulated buff stacks on agents.
+#[derive(Debug, Clone, Default)]
+pub struct BuffTracker {
+    /// Stacks by agent id and buff.
+    stacks: HashMap<(usize, Buff), BuffStacks>,
+
+    /// Coverage since the last flush by agent id and buff.
+    coverage: HashMap<(usize, Buff), Coverage>,
+}
+
+impl BuffTracker {
+    /// Creates a new buff tracker.
+    pub fn new() -> Self {
+        Self {
+            stacks: HashMap::new(),
+            coverage: HashMap::new(),
+        }
+    }
+
+    /// Returns the simulated stacks of a buff on the given agent.
+    pub fn stacks(&self, agent: usize, buff: Buff) -> Option<&BuffStacks> {
+        self.stacks.get(&(agent, buff))
+    }
+
+    /// Applies a buff stack, returning the effective duration added.
+    ///
+    /// Overstack reported by ArcDPS is used if it exceeds the simulated loss.
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply(
+        &mut self,
+        time: u64,
+        agent: usize,
+        buff: Buff,
+        stacking: Stacking,
+        duration: i32,
+        overstack: u32,
+        own: bool,
+        instance: u32,
+    ) -> i32 {
+        let stacks = self
+            .stacks
+            .entry((agent, buff))
+            .or_insert_with(|| BuffStacks::new(stacking, time));
+        let coverage = stacks.advance(time);
+        self.coverage
+            .entry((agent, buff))
+            .or_default()
+            .add(coverage);
+        let lost = stacks.apply(duration, own, instance);
+        let overstack = i32::try_from(overstack).unwrap_or(i32::MAX).min(duration);
+        duration - lost.max(overstack)
+    }
+
+    /// Extends a stack of a buff.
+    ///
+    /// For duration stacking the active stack is extended.
+    /// For intensity stacking the stack with the given instance id is extended,
+    /// the extension is ignored if there is no such stack.
+    pub fn extend(&mut self, time: u64, agent: usize, buff: Buff, duration: i32, instance: u32) {
+        if let Some(stacks) = self.stacks.get_mut(&(agent, buff)) {
+            let coverage = stacks.advance(time);
+            self.coverage
+                .entry((agent, buff))
+                .or_default()
+                .add(coverage);
+            let stack = match stacks.stacking {
+                Stacking::Duration(_) => stacks.stacks.first_mut(),
+                Stacking::Intensity(_) => stacks
+                    .stacks
+                    .iter_mut()
+                    .find(|stack| stack.instance == instance),
+            };
+            if let Some(stack) = stack {
+                stack.remaining += duration;
+            }
+        }
+    }
+
+    /// Removes all stacks of a buff.
+    pub fn remove_all(&mut self, time: u64, agent: usize, buff: Buff) {
+        if let Some(mut stacks) = self.stacks.remove(&(agent, buff)) {
+            let coverage = stacks.advance(time);
+            self.coverage
+                .entry((agent, buff))
+                .or_default()
+                .add(coverage);
+        }
+    }
+
+    /// Removes a single stack of a buff with the given remaining duration.
+    pub fn remove_single(&mut self, time: u64, agent: usize, buff: Buff, duration: i32) {
+        if let Some(stacks) = self.stacks.get_mut(&(agent, buff)) {
+            let coverage = stacks.advance(time);
+            self.coverage
+                .entry((agent, buff))
+                .or_default()
+                .add(coverage);
+            stacks.remove(duration);
+            if stacks.stacks.is_empty() {
+                self.stacks.remove(&(agent, buff));
+            }
+        }
+    }
+
+    /// Advances all stacks to the given time and takes the coverage since the last flush.
+    pub fn flush(&mut self, time: u64) -> Vec<(usize, Buff, Coverage)> {
+        for (key, stacks) in &mut self.stacks {
+            let coverage = stacks.advance(time);
+            self.coverage.entry(*key).or_default().add(coverage);
+        }
+        self.stacks.retain(|_, stacks| stacks.is_active());
+        mem::take(&mut self.coverage)
+            .into_iter()
+            .filter(|(_, coverage)| coverage.active > 0)
+            .map(|((agent, buff), coverage)| (agent, buff, coverage))
+            .collect()
+    }
+}
+
+/// Time covered by a buff.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Coverage {
+    /// Time with any stack active.
+    pub active: u64,
+
+    /// Time with an own stack active.
+    pub own: u64,
+}
+
+impl Coverage {
+    /// Adds another coverage.
+    pub fn add(&mut self, other: Coverage) {
+        self.active += other.active;
+        self.own += other.own;
+    }
+}
+
+/// Simulated stacks of a buff on a single agent.
+#[derive(Debug, Clone)]
+pub struct BuffStacks {
+    /// Stacking behavior of the buff.
+    pub stacking: Stacking,
+
+    /// Time of last update.
+    pub time: u64,
+
+    /// Current stacks.
+    ///
+    /// For duration stacking the first stack is the active one.
+    pub stacks: Vec<BuffStack>,
+}
+
+impl BuffStacks {
+    /// Creates new empty stacks.
+    pub const fn new(stacking: Stacking, time: u64) -> Self {
+        Self {
+            stacking,
+            time,
+            stacks: Vec::new(),
+        }
+    }
+
+    /// Checks whether any stack is active.
+    pub fn is_active(&self) -> bool {
+        !self.stacks.is_empty()
+    }
+
+    /// Advances the stacks to the given time, returning the time covered.
+    pub fn advance(&mut self, time: u64) -> Coverage {
+        let mut elapsed = time.saturating_sub(self.time) as i64;
+        self.time = self.time.max(time);
+        let mut coverage = Coverage::default();
+        match self.stacking {
+            Stacking::Duration(_) => {
+                while elapsed > 0 && !self.stacks.is_empty() {
+                    let active = &mut self.stacks[0];
+                    let consumed = elapsed.min(active.remaining as i64);
+                    active.remaining -= consumed as i32;
+                    elapsed -= consumed;
+                    coverage.active += consumed as u64;
+                    if active.own {
+                        coverage.own += consumed as u64;
+                    }
+                    if active.remaining <= 0 {
+                        self.stacks.remove(0);
+                    }
+                }
+            }
+            Stacking::Intensity(_) => {
+                let longest = |own: bool| {
+                    self.stacks
+                        .iter()
+                        .filter(|stack| !own || stack.own)
+                        .map(|stack| stack.remaining as i64)
+                        .max()
+                        .unwrap_or(0)
+                        .min(elapsed) as u64
+                };
+                coverage.active = longest(false);
+                coverage.own = longest(true);
+                for stack in &mut self.stacks {
+                    stack.remaining = (stack.remaining as i64 - elapsed).max(0) as i32;
+                }
+                self.stacks.retain(|stack| stack.remaining > 0);
+            }
+        }
+        coverage
+    }
+
+    /// Applies a new stack, returning the duration lost.
+    fn apply(&mut self, duration: i32, own: bool, instance: u32) -> i32 {
+        let stack = BuffStack {
+            remaining: duration,
+            own,
+            instance,
+        };
+        if self.stacks.len() < self.stacking.capacity() {
+            self.stacks.push(stack);
+            return 0;
+        }
+
+        // the active duration stack is only replaced without a queue
+        let skip = match self.stacking {
+            Stacking::Duration(capacity) if capacity > 1 => 1,
+            _ => 0,
+        };
+        match self
+            .stacks
+            .iter_mut()
+            .skip(skip)
+            .min_by_key(|stack| stack.remaining)
+        {
+            Some(shortest) if shortest.remaining < duration => {
+                let lost = shortest.remaining;
+                *shortest = stack;
+                lost
+            }
+            _ => duration,
+        }
+    }
+
+    /// Removes the stack closest to the given remaining duration.
+    fn remove(&mut self, duration: i32) {
+        if let Some(index) = self
+            .stacks
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, stack)| (stack.remaining - duration).abs())
+            .map(|(index, _)| index)
+        {
+            self.stacks.remove(index);
+        }
+    }
+}
+
+/// Simulated single buff stack.
+#[derive(Debug, Clone, Copy)]
+pub struct BuffStack {
+    /// Remaining duration.
+    pub remaining: i32,
+
+    /// Whether the stack was applied by us.
+    pub own: bool,
+
+    /// Buff instance id of the stack.
+    pub instance: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUFF: Buff = Buff(1);
+    const AGENT: usize = 1;
+
+    fn stack(remaining: i32, own: bool) -> BuffStack {
+        BuffStack {
+            remaining,
+            own,
+            instance: 0,
+        }
+    }
+
+    #[test]
+    fn advance_duration() {
+        let mut stacks = BuffStacks::new(Stacking::Duration(9), 0);
+        stacks.stacks = vec![stack(1000, true), stack(2000, false)];
+
+        // stacks are consumed one after another
+        let coverage = stacks.advance(1500);
+        assert_eq!(
+            coverage,
+            Coverage {
+                active: 1500,
+                own: 1000
+            }
+        );
+        assert_eq!(stacks.stacks.len(), 1);
+        assert_eq!(stacks.stacks[0].remaining, 1500);
+
+        let coverage = stacks.advance(5000);
+        assert_eq!(
+            coverage,
+            Coverage {
+                active: 1500,
+                own: 0
+            }
+        );
+        assert!(!stacks.is_active());
+    }
+
+    #[test]
+    fn advance_intensity() {
+        let mut stacks = BuffStacks::new(Stacking::Intensity(25), 0);
+        stacks.stacks = vec![stack(1000, true), stack(3000, false)];
+
+        // stacks are consumed simultaneously
+        let coverage = stacks.advance(2000);
+        assert_eq!(
+            coverage,
+            Coverage {
+                active: 2000,
+                own: 1000
+            }
+        );
+        assert_eq!(stacks.stacks.len(), 1);
+        assert_eq!(stacks.stacks[0].remaining, 1000);
+
+        // earlier time is ignored
+        let coverage = stacks.advance(1000);
+        assert_eq!(coverage, Coverage::default());
+        assert_eq!(stacks.stacks[0].remaining, 1000);
+    }
+
+    #[test]
+    fn apply_capacity() {
+        let mut stacks = BuffStacks::new(Stacking::Intensity(2), 0);
+        assert_eq!(stacks.apply(1000, true, 1), 0);
+        assert_eq!(stacks.apply(2000, true, 2), 0);
+
+        // shortest stack is replaced
+        assert_eq!(stacks.apply(3000, true, 3), 1000);
+        assert_eq!(stacks.stacks[0].remaining, 3000);
+
+        // shorter stack than all existing is lost
+        assert_eq!(stacks.apply(500, true, 4), 500);
+    }
+
+    #[test]
+    fn apply_duration_queue() {
+        let mut stacks = BuffStacks::new(Stacking::Duration(2), 0);
+        stacks.apply(1000, true, 1);
+        stacks.apply(2000, true, 2);
+
+        // active stack is not replaced
+        assert_eq!(stacks.apply(3000, true, 3), 2000);
+        assert_eq!(stacks.stacks[0].remaining, 1000);
+        assert_eq!(stacks.stacks[1].remaining, 3000);
+    }
+
+    #[test]
+    fn remove() {
+        let mut stacks = BuffStacks::new(Stacking::Intensity(25), 0);
+        stacks.stacks = vec![stack(1000, true), stack(2000, false), stack(3000, true)];
+
+        // closest remaining duration is removed
+        stacks.remove(1900);
+        let remaining: Vec<_> = stacks.stacks.iter().map(|stack| stack.remaining).collect();
+        assert_eq!(remaining, [1000, 3000]);
+    }
+
+    #[test]
+    fn tracker_remove() {
+        let mut tracker = BuffTracker::new();
+        let stacking = Stacking::Intensity(25);
+        tracker.apply(0, AGENT, BUFF, stacking, 1000, 0, true, 1);
+        tracker.apply(0, AGENT, BUFF, stacking, 2000, 0, false, 2);
+        tracker.remove_single(500, AGENT, BUFF, 500);
+        assert_eq!(tracker.stacks(AGENT, BUFF).unwrap().stacks.len(), 1);
+        tracker.remove_all(1000, AGENT, BUFF);
+        assert!(tracker.stacks(AGENT, BUFF).is_none());
+
+        let coverage = tracker.flush(2000);
+        assert_eq!(
+            coverage,
+            [(
+                AGENT,
+                BUFF,
+                Coverage {
+                    active: 1000,
+                    own: 500
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn extend_duration() {
+        let mut tracker = BuffTracker::new();
+        let stacking = Stacking::Duration(9);
+        tracker.apply(0, AGENT, BUFF, stacking, 1000, 0, true, 1);
+        tracker.apply(0, AGENT, BUFF, stacking, 3000, 0, false, 2);
+        tracker.extend(500, AGENT, BUFF, 2000, 2);
+
+        // active stack is extended regardless of instance
+        let stacks = tracker.stacks(AGENT, BUFF).unwrap();
+        assert_eq!(stacks.stacks[0].remaining, 2500);
+        assert_eq!(stacks.stacks[1].remaining, 3000);
+    }
+
+    #[test]
+    fn extend_intensity() {
+        let mut tracker = BuffTracker::new();
+        let stacking = Stacking::Intensity(25);
+        tracker.apply(0, AGENT, BUFF, stacking, 1000, 0, true, 1);
+        tracker.apply(0, AGENT, BUFF, stacking, 3000, 0, false, 2);
+        tracker.extend(500, AGENT, BUFF, 2000, 2);
+
+        let stacks = tracker.stacks(AGENT, BUFF).unwrap();
+        assert_eq!(stacks.stacks[0].remaining, 500);
+        assert_eq!(stacks.stacks[1].remaining, 4500);
+
+        // unknown instance is ignored
+        tracker.extend(500, AGENT, BUFF, 2000, 3);
+        let stacks = tracker.stacks(AGENT, BUFF).unwrap();
+        assert_eq!(stacks.stacks[0].remaining, 500);
+        assert_eq!(stacks.stacks[1].remaining, 4500);
+    }
 }
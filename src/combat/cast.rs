@@ -1,7 +1,9 @@
-use arcdps::{evtc::AgentKind, Activation, Agent};
+use evtc::{Activation, Agent, AgentKind, Strike};
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
 
 /// Information about a cast (activation).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Cast {
     /// Time of start event or first registered hit.
     pub time: i32,
@@ -43,19 +45,74 @@ impl Cast {
     }
 
     /// Creates a new cast from an individual hit.
-    pub fn from_hit(time: i32, skill: u32, target: &Agent) -> Self {
+    pub fn from_hit(time: i32, skill: u32, hit: Hit) -> Self {
         Self {
             time,
             skill,
             state: CastState::Pre,
             duration: 0,
-            hits: vec![target.into()],
+            hits: vec![hit],
         }
     }
 
-    /// Adds a hit to the cast.
-    pub fn hit(&mut self, target: &Agent) {
-        self.hits.push(target.into())
+    /// Returns the number of landed hits.
+    pub fn hit_count(&self) -> usize {
+        self.hits
+            .iter()
+            .filter(|hit| hit.outcome.is_landed())
+            .count()
+    }
+
+    /// Returns the number of landed hits on the given target species.
+    pub fn target_hits(&self, species: Option<u32>) -> usize {
+        match species {
+            Some(species) => self
+                .hits
+                .iter()
+                .filter(|hit| hit.target == species && hit.outcome.is_landed())
+                .count(),
+            None => 0,
+        }
+    }
+
+    /// Returns the time (ms) between the cast and its last hit.
+    pub fn hit_span(&self) -> i32 {
+        self.hits
+            .iter()
+            .map(|hit| hit.time - self.time)
+            .max()
+            .unwrap_or(0)
+            .max(0)
+    }
+
+    /// Returns the total damage dealt by the hits.
+    pub fn damage(&self) -> i64 {
+        self.hits.iter().map(|hit| hit.damage as i64).sum()
+    }
+
+    /// Returns the number of failed hit attempts per outcome.
+    pub fn failed_hits(&self) -> Vec<(HitOutcome, usize)> {
+        let mut failed: Vec<(HitOutcome, usize)> = Vec::new();
+        for hit in self.hits.iter().filter(|hit| !hit.outcome.is_landed()) {
+            match failed
+                .iter_mut()
+                .find(|(outcome, _)| *outcome == hit.outcome)
+            {
+                Some((_, count)) => *count += 1,
+                None => failed.push((hit.outcome, 1)),
+            }
+        }
+        failed
+    }
+
+    /// Checks whether the cast has a hit on the given agent.
+    pub fn has_hit_on(&self, agent: usize) -> bool {
+        self.hits.iter().any(|hit| hit.agent == agent)
+    }
+
+    /// Adds a hit or hit attempt to the cast.
+    pub fn hit(&mut self, hit: Hit) {
+        self.hits.push(hit)
     }
 
     /// Completes the cast.
@@ -70,7 +127,20 @@ impl Cast {
 }
 
 /// Possible cast states.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    AsRefStr,
+    Serialize,
+    Deserialize,
+)]
 pub enum CastState {
     /// Unknown state.
     #[default]
@@ -90,6 +160,9 @@ pub enum CastState {
 
     /// Interrupted before fire.
     Interrupt,
+
+    /// Instant cast without activation.
+    Instant,
 }
 
 impl From<Activation> for CastState {
@@ -103,20 +176,116 @@ impl From<Activation> for CastState {
     }
 }
 
-/// Information about an individual hit.
-#[derive(Debug, Clone)]
+/// Information about an individual hit or hit attempt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Hit {
+    /// Time of the hit.
+    pub time: i32,
+
+    /// Skill id of the hit, 0 if unknown.
+    #[serde(default)]
+    pub skill: u32,
+
     /// Target species.
     pub target: u32,
+
+    /// Target agent id.
+    pub agent: usize,
+
+    /// Outcome of the strike.
+    pub outcome: HitOutcome,
+
+    /// Damage dealt.
+    pub damage: i32,
 }
 
-impl From<&Agent> for Hit {
-    fn from(target: &Agent) -> Self {
+impl Hit {
+    /// Creates a new hit on the given target.
+    pub fn new(time: i32, skill: u32, target: &Agent, outcome: HitOutcome, damage: i32) -> Self {
         Self {
+            time,
+            skill,
             target: match target.kind() {
                 AgentKind::Player => 0,
                 AgentKind::Npc(species) | AgentKind::Gadget(species) => species as u32,
             },
+            agent: target.id,
+            outcome,
+            damage,
         }
     }
+
+    /// Checks whether the hit was a critical hit.
+    pub fn is_crit(&self) -> bool {
+        self.outcome == HitOutcome::Crit
+    }
+
+    /// Checks whether the hit was a glancing hit.
+    pub fn is_glance(&self) -> bool {
+        self.outcome == HitOutcome::Glance
+    }
+}
+
+/// Possible outcomes of a hit attempt.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, AsRefStr, Serialize, Deserialize,
+)]
+pub enum HitOutcome {
+    /// Regular hit.
+    Normal,
+
+    /// Critical hit.
+    Crit,
+
+    /// Glancing hit.
+    Glance,
+
+    /// Hit interrupted the target.
+    Interrupt,
+
+    /// Hit killed the target.
+    #[strum(serialize = "Killing blow")]
+    KillingBlow,
+
+    /// Hit downed the target.
+    Downed,
+
+    /// Hit was blocked.
+    Block,
+
+    /// Hit was evaded.
+    Evade,
+
+    /// Target was invulnerable.
+    #[strum(serialize = "Invuln")]
+    Absorb,
+
+    /// Hit missed due to blindness.
+    Blind,
+}
+
+impl HitOutcome {
+    /// Converts a strike result to a hit outcome.
+    ///
+    /// Returns [`None`] for strike results not related to damage hits.
+    pub fn from_strike(strike: Strike) -> Option<Self> {
+        match strike {
+            Strike::Normal => Some(Self::Normal),
+            Strike::Crit => Some(Self::Crit),
+            Strike::Glance => Some(Self::Glance),
+            Strike::Interrupt => Some(Self::Interrupt),
+            Strike::KillingBlow => Some(Self::KillingBlow),
+            Strike::Downed => Some(Self::Downed),
+            Strike::Block => Some(Self::Block),
+            Strike::Evade => Some(Self::Evade),
+            Strike::Absorb => Some(Self::Absorb),
+            Strike::Blind => Some(Self::Blind),
+            _ => None,
+        }
+    }
+
+    /// Checks whether the hit landed on the target.
+    pub fn is_landed(&self) -> bool {
+        !matches!(self, Self::Block | Self::Evade | Self::Absorb | Self::Blind)
+    }
 }
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+
+/// Own skills used without a definition.
+#[derive(Debug, Clone, Default)]
+pub struct Discovery {
+    skills: Vec<DiscoveredSkill>,
+}
+
+impl Discovery {
+    /// Creates a new empty discovery.
+    pub const fn new() -> Self {
+        Self { skills: Vec::new() }
+    }
+
+    /// Returns the discovered skills, ordered by first use.
+    pub fn skills(&self) -> &[DiscoveredSkill] {
+        &self.skills
+    }
+
+    /// Clears all discovered skills.
+    pub fn clear(&mut self) {
+        self.skills.clear()
+    }
+
+    /// Registers a cast of the skill.
+    pub fn cast(&mut self, skill: u32) {
+        self.get_or_insert(skill).cast()
+    }
+
+    /// Registers a hit of the skill.
+    pub fn hit(&mut self, skill: u32) {
+        self.get_or_insert(skill).hit()
+    }
+
+    /// Completes the hit counts of the latest casts, for example at fight end.
+    pub fn complete(&mut self) {
+        for skill in &mut self.skills {
+            skill.complete()
+        }
+    }
+
+    fn get_or_insert(&mut self, skill: u32) -> &mut DiscoveredSkill {
+        match self.skills.iter().position(|entry| entry.id == skill) {
+            Some(index) => &mut self.skills[index],
+            None => {
+                self.skills.push(DiscoveredSkill::new(skill));
+                self.skills.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+/// Own skill used without a definition.
+#[derive(Debug, Clone)]
+pub struct DiscoveredSkill {
+    /// Skill id.
+    pub id: u32,
+
+    /// Number of casts.
+    pub casts: usize,
+
+    /// Number of hits.
+    pub hits: usize,
+
+    /// Hits since the latest cast, if not completed yet.
+    pending: Option<usize>,
+
+    /// Number of casts per hit count.
+    pub hit_counts: BTreeMap<usize, usize>,
+}
+
+impl DiscoveredSkill {
+    /// Creates a new discovered skill without uses.
+    pub const fn new(id: u32) -> Self {
+        Self {
+            id,
+            casts: 0,
+            hits: 0,
+            pending: None,
+            hit_counts: BTreeMap::new(),
+        }
+    }
+
+    /// Registers a cast, completing the hit count of the previous cast.
+    pub fn cast(&mut self) {
+        self.complete();
+        self.casts += 1;
+        self.pending = Some(0);
+    }
+
+    /// Registers a hit.
+    pub fn hit(&mut self) {
+        self.hits += 1;
+        if let Some(pending) = &mut self.pending {
+            *pending += 1;
+        }
+    }
+
+    /// Completes the hit count of the latest cast.
+    pub fn complete(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            *self.hit_counts.entry(pending).or_default() += 1;
+        }
+    }
+
+    /// Returns the most common hit count per completed cast.
+    pub fn common_hits(&self) -> Option<usize> {
+        self.hit_counts
+            .iter()
+            .max_by_key(|(hits, count)| (**count, **hits))
+            .map(|(hits, _)| *hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn casts(hits: &[usize]) -> DiscoveredSkill {
+        let mut skill = DiscoveredSkill::new(1);
+        for count in hits {
+            skill.cast();
+            for _ in 0..*count {
+                skill.hit();
+            }
+        }
+        skill
+    }
+
+    #[test]
+    fn common_hits() {
+        let mut skill = casts(&[3, 2, 3, 1]);
+        skill.complete();
+        assert_eq!(skill.casts, 4);
+        assert_eq!(skill.hits, 9);
+        assert_eq!(skill.common_hits(), Some(3));
+    }
+
+    #[test]
+    fn common_hits_tie() {
+        // higher hit count wins a tie
+        let mut skill = casts(&[2, 4]);
+        skill.complete();
+        assert_eq!(skill.common_hits(), Some(4));
+    }
+
+    #[test]
+    fn common_hits_incomplete() {
+        let mut skill = casts(&[5]);
+        assert_eq!(skill.common_hits(), None);
+
+        skill.complete();
+        assert_eq!(skill.common_hits(), Some(5));
+
+        // completing again does not count twice
+        skill.complete();
+        assert_eq!(skill.hit_counts.get(&5), Some(&1));
+    }
+
+    #[test]
+    fn hits_without_cast() {
+        let mut skill = DiscoveredSkill::new(1);
+        skill.hit();
+        skill.complete();
+        assert_eq!(skill.common_hits(), None);
+
+        skill.cast();
+        skill.complete();
+        assert_eq!(skill.common_hits(), Some(0));
+    }
+}
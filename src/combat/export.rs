@@ -0,0 +1,263 @@
+use super::{skill::SkillMap, CombatData};
+use crate::{data::BuffData, history::Fight};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Fight export with resolved skill & buff names.
+#[derive(Debug, Serialize)]
+struct FightExport<'a> {
+    /// Names of skills used in the fight.
+    skills: BTreeMap<u32, String>,
+
+    /// Names of buffs applied in the fight.
+    buffs: BTreeMap<u32, String>,
+
+    /// Exported fight.
+    fight: &'a Fight<CombatData>,
+}
+
+/// Returns a file name prefix for the fight.
+///
+/// Consists of the fight name and the current UTC time.
+pub fn file_prefix(fight: &Fight<CombatData>) -> String {
+    let name: String = fight
+        .name
+        .as_deref()
+        .unwrap_or("Unknown")
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    format!("{name}_{}", format_timestamp(now))
+}
+
+/// Formats seconds since the Unix epoch as `YYYYMMDD-HHMMSS` in UTC.
+fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let secs = secs % 86400;
+
+    // civil date from days since epoch
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}{month:02}{day:02}-{:02}{:02}{:02}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Returns a file name prefix not used by any file with the given suffixes in the directory.
+fn unique_prefix(dir: &Path, prefix: &str, suffixes: &[&str]) -> String {
+    let exists = |prefix: &str| {
+        suffixes
+            .iter()
+            .any(|suffix| dir.join(format!("{prefix}{suffix}")).exists())
+    };
+    if !exists(prefix) {
+        return prefix.into();
+    }
+    (1..)
+        .map(|index| format!("{prefix}_{index}"))
+        .find(|prefix| !exists(prefix))
+        .unwrap()
+}
+
+/// Creates a new file, failing if it already exists.
+fn create_new(path: &Path) -> io::Result<BufWriter<File>> {
+    File::options()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map(BufWriter::new)
+}
+
+/// Exports the fight as JSON into the given directory, returning the path of the written file.
+///
+/// Existing files are never overwritten.
+pub fn export_json(
+    fight: &Fight<CombatData>,
+    skills: &mut SkillMap,
+    buffs: &BuffData,
+    dir: impl AsRef<Path>,
+) -> io::Result<PathBuf> {
+    let dir = dir.as_ref();
+    let prefix = unique_prefix(dir, &file_prefix(fight), &[".json"]);
+    let path = dir.join(format!("{prefix}.json"));
+    let data = &fight.data;
+    let names = data
+        .casts
+        .iter()
+        .map(|cast| cast.skill)
+        .chain(data.breakbar.iter().map(|hit| hit.skill))
+        .map(|id| (id, skills.get_name(id).to_string()))
+        .collect();
+    let buff_names = data
+        .buffs
+        .iter()
+        .map(|apply| (apply.buff.0, buffs.name(apply.buff).into_owned()))
+        .collect();
+    let export = FightExport {
+        skills: names,
+        buffs: buff_names,
+        fight,
+    };
+    let mut file = create_new(&path)?;
+    serde_json::to_writer_pretty(&mut file, &export)?;
+    file.flush()?;
+    Ok(path)
+}
+
+/// Exports the fight as CSV files into the given directory.
+///
+/// Writes one file per log type, returning the paths of the written files.
+/// Existing files are never overwritten.
+pub fn export_csv(
+    fight: &Fight<CombatData>,
+    skills: &mut SkillMap,
+    buffs: &BuffData,
+    dir: impl AsRef<Path>,
+) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    let prefix = unique_prefix(
+        dir,
+        &file_prefix(fight),
+        &[
+            "_casts.csv",
+            "_buffs.csv",
+            "_breakbar.csv",
+            "_transfers.csv",
+        ],
+    );
+    let data = &fight.data;
+    let mut paths = Vec::new();
+
+    let path = dir.join(format!("{prefix}_casts.csv"));
+    let mut file = create_new(&path)?;
+    writeln!(
+        file,
+        "time,skill_id,skill,state,duration,hits,target_hits,damage"
+    )?;
+    for cast in &data.casts {
+        write_row(
+            &mut file,
+            &[
+                &cast.time,
+                &cast.skill,
+                &skills.get_name(cast.skill),
+                &cast.state.as_ref(),
+                &cast.duration,
+                &cast.hit_count(),
+                &cast.target_hits(fight.target),
+                &cast.damage(),
+            ],
+        )?;
+    }
+    file.flush()?;
+    paths.push(path);
+
+    let path = dir.join(format!("{prefix}_buffs.csv"));
+    let mut file = create_new(&path)?;
+    writeln!(file, "time,buff_id,buff,duration,effective,target")?;
+    for apply in &data.buffs {
+        write_row(
+            &mut file,
+            &[
+                &apply.time,
+                &apply.buff.0,
+                &buffs.name(apply.buff),
+                &apply.duration,
+                &apply.effective,
+                &apply.target.name,
+            ],
+        )?;
+    }
+    file.flush()?;
+    paths.push(path);
+
+    let path = dir.join(format!("{prefix}_breakbar.csv"));
+    let mut file = create_new(&path)?;
+    writeln!(file, "time,skill_id,skill,damage,attacker,own,target")?;
+    for hit in &data.breakbar {
+        write_row(
+            &mut file,
+            &[
+                &hit.time,
+                &hit.skill,
+                &skills.get_name(hit.skill),
+                &format!("{:.1}", hit.damage as f32 / 10.0),
+                &hit.attacker.name,
+                &hit.is_own,
+                &hit.target.name,
+            ],
+        )?;
+    }
+    file.flush()?;
+    paths.push(path);
+
+    let path = dir.join(format!("{prefix}_transfers.csv"));
+    let mut file = create_new(&path)?;
+    writeln!(file, "time,condition,stacks,target")?;
+    for transfer in data.transfers.found() {
+        write_row(
+            &mut file,
+            &[
+                &transfer.time,
+                &transfer.condi.as_ref(),
+                &transfer.stacks,
+                &transfer.target.name,
+            ],
+        )?;
+    }
+    file.flush()?;
+    paths.push(path);
+
+    Ok(paths)
+}
+
+/// Writes a CSV row, quoting fields as necessary.
+fn write_row(writer: &mut impl Write, fields: &[&dyn Display]) -> io::Result<()> {
+    let row = fields
+        .iter()
+        .map(|field| {
+            let field = field.to_string();
+            if field.contains([',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    writeln!(writer, "{row}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp() {
+        assert_eq!(format_timestamp(0), "19700101-000000");
+        assert_eq!(format_timestamp(951782400), "20000229-000000");
+        assert_eq!(format_timestamp(1700000000), "20231114-221320");
+    }
+}
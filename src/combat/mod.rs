@@ -2,18 +2,27 @@ pub mod agent;
 pub mod breakbar;
 pub mod buff;
 pub mod cast;
+pub mod discovery;
+pub mod export;
 pub mod player;
 pub mod skill;
+pub mod summary;
 pub mod transfer;
+pub mod tuner;
+pub mod uptime;
 
 pub use self::agent::Agent;
 pub use self::player::Player;
 
-use arcdps::evtc::{self, AgentKind};
+use crate::data::SkillData;
 use breakbar::BreakbarHit;
 use buff::BuffApply;
-use cast::Cast;
+use cast::{Cast, CastState};
+use evtc::AgentKind;
+use serde::{Deserialize, Serialize};
+use std::mem;
 use transfer::TransferTracker;
+use uptime::UptimeTracker;
 
 /// Generates a name with the given parameters.
 pub fn process_name(id: usize, kind: AgentKind, name: Option<&str>) -> String {
@@ -32,12 +41,14 @@ pub fn name_of(agent: &evtc::Agent) -> String {
     process_name(agent.id, agent.kind(), agent.name())
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CombatData {
     pub casts: Vec<Cast>,
     pub buffs: Vec<BuffApply>,
     pub breakbar: Vec<BreakbarHit>,
     pub transfers: TransferTracker,
+    pub uptime: UptimeTracker,
 }
 
 impl CombatData {
@@ -47,6 +58,72 @@ impl CombatData {
             buffs: Vec::new(),
             breakbar: Vec::new(),
             transfers: TransferTracker::new(),
+            uptime: UptimeTracker::new(),
+        }
+    }
+
+    /// Returns the latest cast of the given skill.
+    pub fn latest_cast_mut(&mut self, id: u32) -> Option<&mut Cast> {
+        self.casts.iter_mut().rev().find(|cast| cast.skill == id)
+    }
+
+    /// Adds a cast, keeping the casts sorted by time.
+    pub fn add_cast(&mut self, cast: Cast) {
+        let index = self
+            .casts
+            .iter()
+            .rev()
+            .position(|other| other.time <= cast.time)
+            .unwrap_or(0);
+        self.casts.insert(self.casts.len() - index, cast);
+    }
+
+    /// Re-evaluates the casts against the given skill data.
+    ///
+    /// Casts created from hits are attributed to their current primary skill id.
+    /// Hits no longer belonging to the skill of their cast are split into casts created from hits.
+    /// Casts created from hits are merged into a previous cast within its maximum duration.
+    /// Casts and hits of skills without active definition are removed.
+    pub fn reevaluate(&mut self, data: &SkillData) {
+        let mut casts = Vec::with_capacity(self.casts.len());
+        for mut cast in mem::take(&mut self.casts) {
+            let id = match cast.state {
+                CastState::Pre => data.get(cast.skill).map(|info| info.id),
+                _ => data.contains(cast.skill).then_some(cast.skill),
+            };
+            for hit in mem::take(&mut cast.hits) {
+                // hits without skill id stay with their cast
+                let owner = match hit.skill {
+                    0 => id,
+                    skill => data.get(skill).map(|info| info.id),
+                };
+                match owner {
+                    Some(owner) if Some(owner) == id => cast.hits.push(hit),
+                    Some(owner) => casts.push(Cast::from_hit(hit.time, owner, hit)),
+                    None => {}
+                }
+            }
+            match id {
+                Some(_) if cast.state == CastState::Pre && cast.hits.is_empty() => {}
+                Some(id) => {
+                    cast.skill = id;
+                    casts.push(cast);
+                }
+                None => {}
+            }
+        }
+
+        casts.sort_by_key(|cast| cast.time);
+        for mut cast in casts {
+            let max_duration = data.get(cast.skill).map_or(0, |info| info.max_duration);
+            match self.latest_cast_mut(cast.skill) {
+                Some(prev)
+                    if cast.state == CastState::Pre && cast.time - prev.time <= max_duration =>
+                {
+                    prev.hits.append(&mut cast.hits)
+                }
+                _ => self.casts.push(cast),
+            }
         }
     }
 }
@@ -56,3 +133,87 @@ impl Default for CombatData {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{combat::cast::HitOutcome, data::SkillDef};
+    use cast::Hit;
+
+    fn hit(time: i32, skill: u32) -> Hit {
+        Hit {
+            time,
+            skill,
+            target: 0,
+            agent: 0,
+            outcome: HitOutcome::Normal,
+            damage: 100,
+        }
+    }
+
+    fn cast(time: i32, skill: u32, state: CastState, hits: &[u32]) -> Cast {
+        Cast {
+            hits: hits.iter().map(|skill| hit(time + 100, *skill)).collect(),
+            ..Cast::from_start(time, skill, state)
+        }
+    }
+
+    fn def(id: u32, hit_ids: &[u32]) -> SkillDef {
+        SkillDef {
+            hit_ids: hit_ids.into(),
+            max_duration: Some(1000),
+            ..SkillDef::new(id)
+        }
+    }
+
+    fn casts(data: &CombatData) -> Vec<(u32, CastState, usize)> {
+        data.casts
+            .iter()
+            .map(|cast| (cast.skill, cast.state, cast.hits.len()))
+            .collect()
+    }
+
+    #[test]
+    fn reevaluate_merge() {
+        let mut data = CombatData::new();
+        data.casts = vec![
+            cast(0, 1, CastState::Fire, &[1]),
+            cast(200, 2, CastState::Pre, &[2]),
+            cast(400, 2, CastState::Fire, &[2]),
+        ];
+        data.reevaluate(&SkillData::new([def(1, &[2])]));
+
+        // only casts created from hits are relabeled, hits are merged into the previous cast
+        assert_eq!(
+            casts(&data),
+            [(1, CastState::Fire, 3), (2, CastState::Fire, 0)]
+        );
+    }
+
+    #[test]
+    fn reevaluate_split() {
+        let mut data = CombatData::new();
+        data.casts = vec![cast(0, 1, CastState::Fire, &[1, 2, 2, 3])];
+        data.reevaluate(&SkillData::new([def(1, &[]), def(2, &[])]));
+
+        // hits of the removed hit id form their own cast, unknown hits are dropped
+        assert_eq!(
+            casts(&data),
+            [(1, CastState::Fire, 1), (2, CastState::Pre, 2)]
+        );
+    }
+
+    #[test]
+    fn reevaluate_disabled() {
+        let mut data = CombatData::new();
+        data.casts = vec![
+            cast(0, 1, CastState::Fire, &[1]),
+            cast(200, 2, CastState::Pre, &[2]),
+            cast(400, 3, CastState::Fire, &[0]),
+        ];
+        data.reevaluate(&SkillData::new([def(3, &[])]));
+
+        // hits without skill id stay with their cast
+        assert_eq!(casts(&data), [(3, CastState::Fire, 1)]);
+    }
+}
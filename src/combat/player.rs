@@ -1,5 +1,5 @@
 use crate::combat::process_name;
-use arcdps::{evtc, Profession};
+use evtc::{Profession, Specialization};
 
 /// Information about a player.
 #[derive(Debug, Clone)]
@@ -7,6 +7,7 @@ pub struct Player {
     pub id: usize,
     pub instance_id: u16,
     pub prof: Profession,
+    pub spec: Specialization,
     pub name: String,
 }
 
@@ -18,6 +19,7 @@ impl Player {
             id: src.id,
             instance_id: dst.id as u16,
             prof: dst.prof.into(),
+            spec: dst.elite.into(),
             name: process_name(src.id, kind, src.name()),
         }
     }
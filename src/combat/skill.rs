@@ -1,5 +1,11 @@
-use crate::data::SKILL_OVERRIDES;
-use std::collections::{hash_map::Entry, HashMap};
+use crate::{data::SKILL_OVERRIDES, history::PersistError};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map::Entry, BTreeMap, HashMap},
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::Path,
+};
 
 /// Skill map keeping skill information in memory.
 #[derive(Debug, Clone)]
@@ -7,49 +13,226 @@ pub struct SkillMap {
     /// Internal skill map.
     map: HashMap<u32, Skill>,
 
-    /// Number of non-placeholder entries.
-    cached: usize,
+    /// Skill name overrides.
+    overrides: HashMap<u32, String>,
+
+    /// Game build the cached entries belong to.
+    version: Option<u64>,
+
+    /// Skill names imported from the API.
+    imported: HashMap<u32, String>,
+
+    /// Skill icon file ids imported from the API.
+    icons: HashMap<u32, u32>,
 }
 
 impl SkillMap {
     /// Creates a new skill map.
     pub fn new() -> Self {
+        let overrides: HashMap<_, _> = SKILL_OVERRIDES
+            .iter()
+            .map(|(id, name)| (*id, String::from(*name)))
+            .collect();
         Self {
-            map: Self::override_entries(),
-            cached: 0,
+            map: Self::override_entries(&overrides),
+            overrides,
+            version: None,
+            imported: HashMap::new(),
+            icons: HashMap::new(),
         }
     }
 
     /// Creates skill override entries.
-    fn override_entries() -> HashMap<u32, Skill> {
-        SKILL_OVERRIDES
+    fn override_entries(overrides: &HashMap<u32, String>) -> HashMap<u32, Skill> {
+        overrides
             .iter()
-            .cloned()
-            .map(|(id, name)| (id, Skill::named(name)))
+            .map(|(id, name)| (*id, Skill::named(name)))
             .collect()
     }
 
     /// Returns the number of skill overrides.
-    pub fn overrides() -> usize {
-        SKILL_OVERRIDES.len()
+    pub fn overrides(&self) -> usize {
+        self.overrides.len()
+    }
+
+    /// Sets additional skill name overrides, taking priority over the builtin overrides.
+    ///
+    /// Names from previous overrides are discarded.
+    pub fn set_overrides<'a>(&mut self, names: impl IntoIterator<Item = (u32, &'a str)>) {
+        for id in self.overrides.keys() {
+            self.map.remove(id);
+        }
+        self.overrides = SKILL_OVERRIDES
+            .iter()
+            .copied()
+            .chain(names)
+            .map(|(id, name)| (id, name.into()))
+            .collect();
+        for (id, name) in &self.overrides {
+            self.map.insert(*id, Skill::named(name));
+        }
     }
 
     /// Returns the number of cached non-placeholder skill entries.
+    ///
+    /// Overrides are not counted.
     pub fn cached(&self) -> usize {
-        self.cached
+        self.map
+            .iter()
+            .filter(|(id, skill)| !skill.is_placeholder && !self.overrides.contains_key(id))
+            .count()
+    }
+
+    /// Returns the game build the cached entries belong to.
+    pub fn version(&self) -> Option<u64> {
+        self.version
+    }
+
+    /// Sets the current game build.
+    ///
+    /// Cached entries are reset if they belong to an older build.
+    /// Older builds, for example from replayed logs, are ignored.
+    pub fn set_version(&mut self, build: u64) {
+        match self.version {
+            Some(version) if version >= build => {}
+            Some(_) => {
+                self.reset();
+                self.version = Some(build);
+            }
+            None => self.version = Some(build),
+        }
+    }
+
+    /// Saves the cached skill names to a file at the given path.
+    ///
+    /// Overrides and placeholders are not saved.
+    pub fn save_file(&self, path: impl AsRef<Path>) -> Result<(), PersistError> {
+        let file = BufWriter::new(File::create(path).map_err(|_| PersistError::FailedToWrite)?);
+        let contents = SkillCacheFile {
+            version: self.version,
+            skills: self
+                .map
+                .iter()
+                .filter(|(id, skill)| {
+                    !skill.is_placeholder
+                        && !self.overrides.contains_key(id)
+                        && self.imported.get(id) != Some(&skill.name)
+                })
+                .map(|(id, skill)| (*id, skill.name.clone()))
+                .collect(),
+        };
+        serde_json::to_writer(file, &contents).map_err(|_| PersistError::FailedToWrite)
+    }
+
+    /// Loads cached skill names from a file at the given path, returning the amount of names loaded.
+    ///
+    /// Names are only loaded if the file belongs to the current game build or the build is not known yet.
+    /// Existing entries are kept.
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<usize, PersistError> {
+        let contents = fs::read_to_string(path)?;
+        let file: SkillCacheFile =
+            serde_json::from_str(&contents).map_err(|_| PersistError::Invalid)?;
+        match (self.version, file.version) {
+            (Some(current), Some(version)) if version < current => return Ok(0),
+            (None, version) => self.version = version,
+            _ => {}
+        }
+        let count = file.skills.len();
+        for (id, name) in file.skills {
+            self.try_replace_with(id, || Skill::named(&name));
+        }
+        Ok(count)
+    }
+
+    /// Imports skill names and icons from a file at the given path, returning the amount of skills imported.
+    ///
+    /// The file is expected in the format of the GW2 API `/v2/skills` endpoint.
+    /// Imported names are used for skills without a name from combat.
+    /// Previously imported skills are replaced.
+    pub fn import_api(&mut self, path: impl AsRef<Path>) -> Result<usize, PersistError> {
+        let file = BufReader::new(File::open(path)?);
+        let skills: Vec<ApiSkill> =
+            serde_json::from_reader(file).map_err(|_| PersistError::Invalid)?;
+
+        self.imported.clear();
+        self.icons.clear();
+
+        let count = skills.len();
+        for skill in skills {
+            if let Some(icon) = skill.icon.as_deref().and_then(ApiSkill::icon_file_id) {
+                self.icons.insert(skill.id, icon);
+            }
+            if !skill.name.is_empty() {
+                self.imported.insert(skill.id, skill.name);
+            }
+        }
+
+        // replace existing placeholders
+        for (id, skill) in &mut self.map {
+            if skill.is_placeholder {
+                if let Some(name) = self.imported.get(id) {
+                    *skill = Skill::named(name);
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Returns the number of skill names imported from the API.
+    pub fn imported(&self) -> usize {
+        self.imported.len()
+    }
+
+    /// Returns the number of skill icons imported from the API.
+    pub fn imported_icons(&self) -> usize {
+        self.icons.len()
+    }
+
+    /// Returns the amount of known skills covered by the import and the total amount of known skills.
+    ///
+    /// Overrides are not included.
+    pub fn import_coverage(&self) -> (usize, usize) {
+        self.coverage(
+            self.map
+                .keys()
+                .copied()
+                .filter(|id| !self.overrides.contains_key(id)),
+        )
+    }
+
+    /// Returns the amount of the given skill ids covered by the import and the total amount of ids.
+    pub fn coverage(&self, ids: impl IntoIterator<Item = u32>) -> (usize, usize) {
+        ids.into_iter().fold((0, 0), |(covered, total), id| {
+            if self.imported.contains_key(&id) {
+                (covered + 1, total + 1)
+            } else {
+                (covered, total + 1)
+            }
+        })
+    }
+
+    /// Returns the icon file id for the given skill id.
+    pub fn icon(&self, id: u32) -> Option<u32> {
+        self.icons.get(&id).copied()
     }
 
     /// Resets the stored skill information.
     pub fn reset(&mut self) {
-        self.map = Self::override_entries();
-        self.cached = 0;
+        self.map = Self::override_entries(&self.overrides);
     }
 
     /// Returns the skill information for the given id.
     ///
     /// Inserts a placeholder if not present.
     pub fn get(&mut self, id: u32) -> &Skill {
-        self.map.entry(id).or_insert_with(|| Skill::unnamed(id))
+        let imported = &self.imported;
+        self.map
+            .entry(id)
+            .or_insert_with(|| match imported.get(&id) {
+                Some(name) => Skill::named(name),
+                None => Skill::unnamed(id),
+            })
     }
 
     /// Returns the skill name for the given id.
@@ -69,24 +252,25 @@ impl SkillMap {
                 }
                 value
             }
-            Entry::Vacant(vacant) => {
-                let skill = create();
-                if !skill.is_placeholder {
-                    self.cached += 1;
-                }
-                vacant.insert(skill)
-            }
+            Entry::Vacant(vacant) => vacant.insert(create()),
         }
     }
 
     /// Attempts to register a skill.
     pub fn try_register(&mut self, id: u32, skill_name: Option<&str>) -> &Skill {
-        self.try_replace_with(id, || Skill::from_combat(id, skill_name))
+        let name = match skill_name {
+            Some(name) if !name.is_empty() => Some(name),
+            _ => self.imported.get(&id).map(String::as_str),
+        };
+        let skill = Skill::from_combat(id, name);
+        self.try_replace_with(id, || skill)
     }
 
     /// Attempts to duplicate a skill.
+    ///
+    /// Skills with an imported name are not duplicated.
     pub fn try_duplicate(&mut self, id: u32, from: u32) {
-        if id != from {
+        if id != from && !self.imported.contains_key(&id) {
             if let Some(Skill {
                 is_placeholder: false,
                 name,
@@ -99,6 +283,42 @@ impl SkillMap {
     }
 }
 
+impl Default for SkillMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Skill cache file contents.
+#[derive(Debug, Serialize, Deserialize)]
+struct SkillCacheFile {
+    version: Option<u64>,
+    skills: BTreeMap<u32, String>,
+}
+
+/// Skill entry in the format of the GW2 API.
+#[derive(Debug, Deserialize)]
+struct ApiSkill {
+    id: u32,
+
+    #[serde(default)]
+    name: String,
+
+    #[serde(default)]
+    icon: Option<String>,
+}
+
+impl ApiSkill {
+    /// Extracts the file id from an icon render URL.
+    ///
+    /// Render URLs end with `/file/<signature>/<file id>.png`.
+    fn icon_file_id(url: &str) -> Option<u32> {
+        let file = url.rsplit('/').next()?;
+        let (id, _) = file.split_once('.')?;
+        id.parse().ok()
+    }
+}
+
 /// Information about a skill.
 #[derive(Debug, Clone)]
 pub struct Skill {
@@ -144,3 +364,31 @@ impl Skill {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_overrides() {
+        let mut skills = SkillMap::new();
+        skills.try_register(1, None);
+        assert_eq!(skills.cached(), 0);
+
+        // placeholder replaced by name from combat
+        skills.try_register(1, Some("First"));
+        skills.try_register(2, Some("Second"));
+        assert_eq!(skills.cached(), 2);
+
+        // overridden names are not counted
+        skills.set_overrides([(1, "Override")]);
+        assert_eq!(skills.get_name(1), "Override");
+        assert_eq!(skills.cached(), 1);
+
+        // removed overrides leave no entry behind
+        skills.set_overrides([]);
+        assert_eq!(skills.cached(), 1);
+        skills.set_overrides([(2, "Override")]);
+        assert_eq!(skills.cached(), 0);
+    }
+}
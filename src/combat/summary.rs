@@ -0,0 +1,121 @@
+use super::cast::Cast;
+use std::cmp::Reverse;
+
+/// Damage summary for a single skill.
+#[derive(Debug, Clone)]
+pub struct SkillSummary {
+    /// Summarized skill.
+    pub skill: u32,
+
+    /// Number of casts.
+    pub casts: usize,
+
+    /// Total damage dealt.
+    pub damage: i64,
+
+    /// Total time spent in animation.
+    pub duration: i64,
+
+    /// Damage dealt by casts with known animation time.
+    pub timed_damage: i64,
+}
+
+impl SkillSummary {
+    /// Creates a new empty summary for the skill.
+    pub const fn new(skill: u32) -> Self {
+        Self {
+            skill,
+            casts: 0,
+            damage: 0,
+            duration: 0,
+            timed_damage: 0,
+        }
+    }
+
+    /// Adds a cast to the summary.
+    pub fn add(&mut self, cast: &Cast) {
+        self.casts += 1;
+        let damage = cast.damage();
+        self.damage += damage;
+        if cast.duration > 0 {
+            self.duration += cast.duration as i64;
+            self.timed_damage += damage;
+        }
+    }
+
+    /// Returns the average damage per cast.
+    pub fn average_damage(&self) -> f64 {
+        if self.casts > 0 {
+            self.damage as f64 / self.casts as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the damage per second of animation time, if the animation time is known.
+    ///
+    /// Only casts with known animation time are included, damage from other casts is ignored.
+    pub fn damage_per_second(&self) -> Option<f64> {
+        if self.duration > 0 {
+            Some(1000.0 * self.timed_damage as f64 / self.duration as f64)
+        } else {
+            None
+        }
+    }
+
+    /// Summarizes the given casts per skill, ordered by total damage.
+    pub fn from_casts<'a>(casts: impl IntoIterator<Item = &'a Cast>) -> Vec<Self> {
+        let mut summaries: Vec<Self> = Vec::new();
+        for cast in casts {
+            match summaries
+                .iter_mut()
+                .find(|summary| summary.skill == cast.skill)
+            {
+                Some(summary) => summary.add(cast),
+                None => {
+                    let mut summary = Self::new(cast.skill);
+                    summary.add(cast);
+                    summaries.push(summary);
+                }
+            }
+        }
+        summaries.sort_by_key(|summary| Reverse(summary.damage));
+        summaries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::cast::{CastState, Hit, HitOutcome};
+
+    fn cast(state: CastState, duration: i32, damage: i32) -> Cast {
+        let hit = Hit {
+            time: 0,
+            skill: 1,
+            target: 0,
+            agent: 0,
+            outcome: HitOutcome::Normal,
+            damage,
+        };
+        Cast {
+            duration,
+            hits: vec![hit],
+            ..Cast::from_start(0, 1, state)
+        }
+    }
+
+    #[test]
+    fn damage_per_second() {
+        let summary = SkillSummary::from_casts(&[
+            cast(CastState::Fire, 500, 1000),
+            cast(CastState::Pre, 0, 3000),
+            cast(CastState::Instant, 0, 2000),
+        ]);
+        assert_eq!(summary[0].damage, 6000);
+        assert_eq!(summary[0].damage_per_second(), Some(2000.0));
+
+        let summary = SkillSummary::from_casts(&[cast(CastState::Pre, 0, 1000)]);
+        assert_eq!(summary[0].damage_per_second(), None);
+    }
+}
@@ -1,5 +1,6 @@
 use super::agent::Agent;
 use log::debug;
+use serde::{Deserialize, Serialize};
 
 pub use crate::data::Condition;
 
@@ -7,15 +8,17 @@ pub use crate::data::Condition;
 pub const TIME_EPSILON: u32 = 10;
 
 /// Transfer tracking.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransferTracker {
     /// Detected transfers.
     transfers: Vec<Transfer>,
 
     /// Condition removes.
+    #[serde(skip)]
     remove: Vec<Remove>,
 
     /// Condition applies as transfer candidates.
+    #[serde(skip)]
     apply: Vec<Apply>,
 }
 
@@ -162,7 +165,7 @@ impl Remove {
 }
 
 /// Information about a condition transfer.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transfer {
     /// Time of the transfer.
     pub time: i32,
@@ -0,0 +1,140 @@
+use super::{
+    cast::{Cast, CastState},
+    CombatData,
+};
+use crate::history::Fight;
+use std::{cmp::Reverse, collections::BTreeMap};
+
+/// Share of casts allowed to fall below the suggested expected hits.
+const EXPECTED_TOLERANCE: f64 = 0.1;
+
+/// Granularity (ms) of the suggested maximum duration.
+const DURATION_STEP: u32 = 100;
+
+/// Observed hit counts of a single skill across casts.
+#[derive(Debug, Clone)]
+pub struct HitStats {
+    /// Observed skill.
+    pub skill: u32,
+
+    /// Number of casts with landed hits.
+    pub casts: usize,
+
+    /// Number of casts per cleave hit count.
+    pub cleave: BTreeMap<usize, usize>,
+
+    /// Number of casts per hit count on the fight target.
+    pub target: BTreeMap<usize, usize>,
+
+    /// Longest time (ms) between a cast and its last hit.
+    pub max_span: i32,
+}
+
+impl HitStats {
+    /// Creates new empty stats for the skill.
+    pub const fn new(skill: u32) -> Self {
+        Self {
+            skill,
+            casts: 0,
+            cleave: BTreeMap::new(),
+            target: BTreeMap::new(),
+            max_span: 0,
+        }
+    }
+
+    /// Adds a cast to the stats.
+    ///
+    /// Interrupted casts and casts without landed hits are ignored.
+    pub fn add(&mut self, cast: &Cast, target: Option<u32>) {
+        let hits = cast.hit_count();
+        if cast.state == CastState::Interrupt || hits == 0 {
+            return;
+        }
+        self.casts += 1;
+        *self.cleave.entry(hits).or_default() += 1;
+        if target.is_some() {
+            *self.target.entry(cast.target_hits(target)).or_default() += 1;
+        }
+        self.max_span = self.max_span.max(cast.hit_span());
+    }
+
+    /// Collects stats per skill from the given fights, ordered by number of casts.
+    pub fn from_fights<'a>(fights: impl IntoIterator<Item = &'a Fight<CombatData>>) -> Vec<Self> {
+        let mut stats: Vec<Self> = Vec::new();
+        for fight in fights {
+            for cast in &fight.data.casts {
+                match stats.iter_mut().find(|stats| stats.skill == cast.skill) {
+                    Some(stats) => stats.add(cast, fight.target),
+                    None => {
+                        let mut new = Self::new(cast.skill);
+                        new.add(cast, fight.target);
+                        stats.push(new);
+                    }
+                }
+            }
+        }
+        stats.retain(|stats| stats.casts > 0);
+        stats.sort_by_key(|stats| Reverse(stats.casts));
+        stats
+    }
+
+    /// Returns the hit count distribution used for suggestions.
+    ///
+    /// Prefers hits on the fight target, if any were observed.
+    pub fn distribution(&self) -> &BTreeMap<usize, usize> {
+        if self.target.keys().any(|hits| *hits > 0) {
+            &self.target
+        } else {
+            &self.cleave
+        }
+    }
+
+    /// Suggests definition values from the observed hits.
+    pub fn suggest(&self) -> Suggestion {
+        let dist = self.distribution();
+        let total: usize = dist.values().sum();
+
+        // most common hit count, larger on ties
+        let hits = dist
+            .iter()
+            .max_by_key(|(hits, count)| (**count, **hits))
+            .map(|(hits, _)| *hits)
+            .unwrap_or(0);
+
+        // lowest hit count reached by most casts
+        let allowed = (total as f64 * EXPECTED_TOLERANCE) as usize;
+        let mut below = 0;
+        let mut expected = hits;
+        for (count, casts) in dist {
+            below += casts;
+            if below > allowed {
+                expected = *count;
+                break;
+            }
+        }
+
+        let max_duration = u32::try_from(self.max_span)
+            .ok()
+            .filter(|span| *span > 0)
+            .map(|span| (span.div_ceil(DURATION_STEP) * DURATION_STEP) as i32);
+
+        Suggestion {
+            hits,
+            expected: expected.min(hits),
+            max_duration,
+        }
+    }
+}
+
+/// Suggested definition values for a skill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Suggestion {
+    /// Total amount of hits.
+    pub hits: usize,
+
+    /// Minimum amount of hits expected.
+    pub expected: usize,
+
+    /// Maximum duration (ms) to count as one cast.
+    pub max_duration: Option<i32>,
+}
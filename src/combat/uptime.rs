@@ -0,0 +1,133 @@
+use super::{
+    agent::Agent,
+    buff::{Buff, Coverage},
+    player::Player,
+};
+use serde::{Deserialize, Serialize};
+
+/// Buff uptime of squad members.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UptimeTracker {
+    /// Time of the last update.
+    pub updated: u64,
+
+    /// Uptimes per player.
+    pub players: Vec<PlayerUptime>,
+}
+
+impl UptimeTracker {
+    /// Creates a new uptime tracker.
+    pub const fn new() -> Self {
+        Self {
+            updated: 0,
+            players: Vec::new(),
+        }
+    }
+
+    /// Adds buff coverage for a player.
+    pub fn add(&mut self, player: &Player, buff: Buff, coverage: Coverage) {
+        let uptime = match self
+            .players
+            .iter_mut()
+            .position(|uptime| uptime.id == player.id)
+        {
+            Some(index) => &mut self.players[index],
+            None => {
+                self.players.push(PlayerUptime::new(player));
+                self.players.last_mut().unwrap()
+            }
+        };
+        uptime.add(buff, coverage);
+    }
+
+    /// Returns all buffs with uptime on any player.
+    pub fn buffs(&self) -> Vec<Buff> {
+        let mut buffs: Vec<_> = self
+            .players
+            .iter()
+            .flat_map(|player| player.buffs.iter().map(|uptime| uptime.buff))
+            .collect();
+        buffs.sort();
+        buffs.dedup();
+        buffs
+    }
+}
+
+impl Default for UptimeTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Buff uptime of a single player.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerUptime {
+    /// Player agent id.
+    pub id: usize,
+
+    /// Player information.
+    pub player: Agent,
+
+    /// Uptimes per buff.
+    pub buffs: Vec<BuffUptime>,
+}
+
+impl PlayerUptime {
+    /// Creates a new empty uptime for the player.
+    pub fn new(player: &Player) -> Self {
+        Self {
+            id: player.id,
+            player: player.into(),
+            buffs: Vec::new(),
+        }
+    }
+
+    /// Returns the uptime for the given buff.
+    pub fn get(&self, buff: Buff) -> Option<&BuffUptime> {
+        self.buffs.iter().find(|uptime| uptime.buff == buff)
+    }
+
+    /// Adds buff coverage.
+    pub fn add(&mut self, buff: Buff, coverage: Coverage) {
+        match self.buffs.iter_mut().find(|uptime| uptime.buff == buff) {
+            Some(uptime) => uptime.coverage.add(coverage),
+            None => self.buffs.push(BuffUptime { buff, coverage }),
+        }
+    }
+}
+
+/// Uptime of a single buff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuffUptime {
+    /// Buff.
+    pub buff: Buff,
+
+    /// Time covered by the buff.
+    pub coverage: Coverage,
+}
+
+impl BuffUptime {
+    /// Returns the time covered by own stacks.
+    pub fn own(&self) -> u64 {
+        self.coverage.own
+    }
+
+    /// Returns the time covered by stacks from other sources only.
+    pub fn others(&self) -> u64 {
+        self.coverage.active - self.coverage.own
+    }
+
+    /// Returns the uptime as percentages of total, own and others for the given duration.
+    pub fn percentages(&self, duration: u64) -> (f32, f32, f32) {
+        if duration > 0 {
+            let percent = |time: u64| 100.0 * time as f32 / duration as f32;
+            (
+                percent(self.coverage.active),
+                percent(self.own()),
+                percent(self.others()),
+            )
+        } else {
+            (0.0, 0.0, 0.0)
+        }
+    }
+}
@@ -1,68 +1,199 @@
-use num_enum::{IntoPrimitive, TryFromPrimitive};
-use strum::AsRefStr;
-
-/// Applied buff.
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    AsRefStr,
-    IntoPrimitive,
-    TryFromPrimitive,
-)]
-#[repr(u32)]
-pub enum Buff {
-    #[strum(serialize = "Quick")]
-    Quickness = 1187,
-
-    #[strum(serialize = "Alac")]
-    Alacrity = 30328,
-
-    #[strum(serialize = "Arc Power")]
-    ArcanePower = 5582,
-
-    #[strum(serialize = "Spider")]
-    SpiderVenom = 13036,
-
-    #[strum(serialize = "Skale")]
-    SkaleVenom = 13054,
-
-    #[strum(serialize = "Devourer")]
-    DevourerVenom = 13094,
-
-    #[strum(serialize = "Basi")]
-    BasiliskVenom = 13133,
-
-    #[strum(serialize = "Soul Stone")]
-    SouleStoneVenom = 49038,
+use super::{BuffCategory, BuffDef, Condition, DataSource, LoadError, StackingType};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{borrow::Cow, collections::HashMap, fs::File, io::BufReader, iter, path::Path};
+
+/// Applied buff, identified by the primary id of its definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(transparent)]
+pub struct Buff(pub u32);
+
+impl<'de> Deserialize<'de> for Buff {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// Buff as id or as variant name of the previous hardcoded buffs.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Id(u32),
+            Legacy(String),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Id(id) => Ok(Self(id)),
+            Repr::Legacy(name) => LEGACY_NAMES
+                .iter()
+                .find(|(legacy, _)| *legacy == name)
+                .map(|(_, id)| Self(*id))
+                .ok_or_else(|| serde::de::Error::custom(format!("unknown buff \"{name}\""))),
+        }
+    }
+}
 
-    #[strum(serialize = "Dwarf")]
-    #[num_enum(alternatives = [33330])]
-    RiteOfTheGreatDwarf = 26596,
+/// Variant names of the previous hardcoded buffs with their ids.
+const LEGACY_NAMES: &[(&str, u32)] = &[
+    ("Quickness", 1187),
+    ("Alacrity", 30328),
+    ("ArcanePower", 5582),
+    ("SpiderVenom", 13036),
+    ("SkaleVenom", 13054),
+    ("DevourerVenom", 13094),
+    ("BasiliskVenom", 13133),
+    ("SouleStoneVenom", 49038),
+    ("RiteOfTheGreatDwarf", 26596),
+    ("AshesOfTheJust", 41957),
+    ("BearStance", 40045),
+    ("DolyakStance", 41815),
+    ("VultureStance", 44651),
+    ("MoaStance", 45038),
+    ("GriffonStance", 46280),
+    ("OneWolfPack", 44139),
+];
+
+impl BuffDef {
+    /// Returns the buff tracked by the definition.
+    pub const fn buff(&self) -> Buff {
+        Buff(self.id)
+    }
+
+    /// Returns the stacking behavior of the buff.
+    pub const fn stacking(&self) -> Stacking {
+        match self.stacking {
+            StackingType::Duration => Stacking::Duration(self.stacks),
+            StackingType::Intensity => Stacking::Intensity(self.stacks),
+        }
+    }
+}
 
-    #[strum(serialize = "AoJ")]
-    AshesOfTheJust = 41957,
+/// Buff data.
+#[derive(Debug, Clone)]
+pub struct BuffData {
+    /// Active buff definitions.
+    defs: Vec<BuffDef>,
 
-    #[strum(serialize = "Bear")]
-    BearStance = 40045,
+    /// Mapping of buff ids including alternative ids to definition index.
+    map: HashMap<u32, usize>,
+}
 
-    #[strum(serialize = "Dolyak")]
-    DolyakStance = 41815,
+impl BuffData {
+    /// Creates new buff data with the given definitions.
+    ///
+    /// Later definitions replace earlier definitions with the same id entirely, including their alternative ids.
+    /// Disabled definitions remove earlier definitions with the same id.
+    /// Definitions using condition ids are ignored, conditions are tracked separately.
+    pub fn new(buffs: impl IntoIterator<Item = BuffDef>) -> Self {
+        let mut defs = Vec::<BuffDef>::new();
+        for buff in buffs {
+            defs.retain(|other| other.id != buff.id);
+            if buff.enabled && !Self::is_condition(&buff) {
+                defs.push(buff);
+            }
+        }
+
+        let mut map = HashMap::with_capacity(defs.len());
+        for (index, def) in defs.iter().enumerate() {
+            map.insert(def.id, index);
+            for alt_id in &def.alt_ids {
+                map.insert(*alt_id, index);
+            }
+        }
+
+        Self { defs, map }
+    }
+
+    /// Checks whether the definition uses a condition id.
+    fn is_condition(def: &BuffDef) -> bool {
+        iter::once(def.id)
+            .chain(def.alt_ids.iter().copied())
+            .any(|id| Condition::try_from(id).is_ok())
+    }
+
+    /// Creates new buff data with the defaults.
+    pub fn with_defaults() -> Self {
+        Self::new(Self::iter_defaults())
+    }
+
+    /// Creates new buff data from the given source.
+    pub fn from_source(source: DataSource, custom: impl IntoIterator<Item = BuffDef>) -> Self {
+        match source {
+            DataSource::Defaults => Self::with_defaults(),
+            DataSource::Custom => Self::new(custom),
+            DataSource::Merged => Self::new(Self::iter_defaults().chain(custom)),
+        }
+    }
+
+    /// Returns an iterator over the defaults.
+    fn iter_defaults() -> impl Iterator<Item = BuffDef> {
+        include!(concat!(env!("OUT_DIR"), "/buffs.rs")).into_iter()
+    }
+
+    /// Returns the number of active definitions.
+    pub fn len(&self) -> usize {
+        self.defs.len()
+    }
+
+    /// Checks whether there are no active definitions.
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+
+    /// Retrieves the [`BuffDef`] for the buff id.
+    ///
+    /// This includes alternative ids.
+    pub fn get(&self, id: u32) -> Option<&BuffDef> {
+        self.map.get(&id).map(|index| &self.defs[*index])
+    }
+
+    /// Returns the display name of the buff.
+    ///
+    /// Falls back to the buff id for buffs without definition.
+    pub fn name(&self, buff: Buff) -> Cow<'_, str> {
+        match self.get(buff.0) {
+            Some(def) => Cow::Borrowed(&def.name),
+            None => Cow::Owned(buff.0.to_string()),
+        }
+    }
+
+    /// Returns a key to sort buffs by category and definition order.
+    ///
+    /// Buffs without definition are sorted last.
+    pub fn sort_key(&self, buff: Buff) -> (BuffCategory, usize) {
+        match self.map.get(&buff.0) {
+            Some(index) => (self.defs[*index].category, *index),
+            None => (BuffCategory::Other, usize::MAX),
+        }
+    }
+
+    /// Attempts to read definitions from a given file path.
+    pub fn read_file(path: impl AsRef<Path>) -> Result<Vec<BuffDef>, LoadError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|err| LoadError::from_io(path, err))?;
+        serde_yaml::from_reader(BufReader::new(file)).map_err(|err| LoadError::from_yaml(path, err))
+    }
+}
 
-    #[strum(serialize = "Vulture")]
-    VultureStance = 44651,
+impl Default for BuffData {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
 
-    #[strum(serialize = "Moa")]
-    MoaStance = 45038,
+/// Stacking behavior of a buff with the maximum amount of stacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stacking {
+    /// Stacks in duration, only a single stack is active at a time.
+    Duration(usize),
 
-    #[strum(serialize = "Griffon")]
-    GriffonStance = 46280,
+    /// Stacks in intensity, all stacks are active at the same time.
+    Intensity(usize),
+}
 
-    #[strum(serialize = "OWP")]
-    OneWolfPack = 44139,
+impl Stacking {
+    /// Returns the maximum amount of stacks.
+    pub const fn capacity(&self) -> usize {
+        match self {
+            Self::Duration(capacity) | Self::Intensity(capacity) => *capacity,
+        }
+    }
 }
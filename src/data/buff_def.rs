@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+/// Buff definition parsed from a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuffDef {
+    /// Buff id.
+    pub id: u32,
+
+    /// Alternative buff ids tracked as the same buff.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alt_ids: Vec<u32>,
+
+    /// Short name to display.
+    pub name: String,
+
+    /// Whether the definition is active.
+    #[serde(default = "default_as_true", skip_serializing_if = "is_true")]
+    pub enabled: bool,
+
+    /// Category of the buff.
+    #[serde(default)]
+    pub category: BuffCategory,
+
+    /// Stacking type of the buff.
+    pub stacking: StackingType,
+
+    /// Maximum amount of stacks.
+    pub stacks: usize,
+}
+
+/// Category of a buff.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum BuffCategory {
+    Boon,
+    Venom,
+    Stance,
+
+    #[default]
+    Other,
+}
+
+/// Stacking type of a buff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StackingType {
+    /// Stacks in duration.
+    Duration,
+
+    /// Stacks in intensity.
+    Intensity,
+}
+
+fn default_as_true() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
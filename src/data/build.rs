@@ -0,0 +1,36 @@
+use super::SkillDef;
+use evtc::{Profession, Specialization};
+use std::fmt;
+
+/// Profession and elite specialization of a character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Build {
+    /// Profession.
+    pub profession: Profession,
+
+    /// Elite specialization.
+    pub spec: Specialization,
+}
+
+impl Build {
+    /// Creates a new build.
+    pub const fn new(profession: Profession, spec: Specialization) -> Self {
+        Self { profession, spec }
+    }
+
+    /// Checks whether the skill definition applies to the build.
+    pub fn matches(&self, def: &SkillDef) -> bool {
+        // definitions without profession or specialization apply to all
+        def.profession.unwrap_or(self.profession) == self.profession
+            && def.spec.unwrap_or(self.spec) == self.spec
+    }
+}
+
+impl fmt::Display for Build {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.spec {
+            Specialization::Unknown => write!(f, "{:?}", self.profession),
+            spec => write!(f, "{spec:?} {:?}", self.profession),
+        }
+    }
+}
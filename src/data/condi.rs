@@ -1,4 +1,5 @@
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde::{Deserialize, Serialize};
 use strum::AsRefStr;
 
 /// Condition.
@@ -14,6 +15,8 @@ use strum::AsRefStr;
     AsRefStr,
     IntoPrimitive,
     TryFromPrimitive,
+    Serialize,
+    Deserialize,
 )]
 #[repr(u32)]
 pub enum Condition {
@@ -0,0 +1,326 @@
+use super::{BuffDef, Condition, SkillDef};
+use std::{
+    collections::HashMap,
+    fmt, io, iter,
+    path::{Path, PathBuf},
+};
+
+/// Error while loading skill definitions from a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError {
+    /// Path of the file.
+    pub path: PathBuf,
+
+    /// Kind of error.
+    pub kind: LoadErrorKind,
+}
+
+impl LoadError {
+    /// Creates a new load error for the file path.
+    pub fn new(path: impl Into<PathBuf>, kind: LoadErrorKind) -> Self {
+        Self {
+            path: path.into(),
+            kind,
+        }
+    }
+
+    /// Creates a load error from an IO error.
+    pub fn from_io(path: impl Into<PathBuf>, err: io::Error) -> Self {
+        let kind = match err.kind() {
+            io::ErrorKind::NotFound => LoadErrorKind::NotFound,
+            _ => LoadErrorKind::FailedToRead(err.to_string()),
+        };
+        Self::new(path, kind)
+    }
+
+    /// Creates a load error from an IO error while writing.
+    pub fn from_write_io(path: impl Into<PathBuf>, err: io::Error) -> Self {
+        Self::new(path, LoadErrorKind::FailedToWrite(err.to_string()))
+    }
+
+    /// Creates a load error from a parsing error.
+    pub fn from_yaml(path: impl Into<PathBuf>, err: serde_yaml::Error) -> Self {
+        let location = err.location();
+        Self::new(
+            path,
+            LoadErrorKind::Invalid {
+                line: location.as_ref().map(|location| location.line()),
+                column: location.as_ref().map(|location| location.column()),
+                message: err.to_string(),
+            },
+        )
+    }
+
+    /// Returns the file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path.display();
+        match &self.kind {
+            LoadErrorKind::NotFound => write!(f, "{path}: not found"),
+            LoadErrorKind::FailedToRead(message) | LoadErrorKind::FailedToWrite(message) => {
+                write!(f, "{path}: {message}")
+            }
+            LoadErrorKind::Invalid {
+                line: Some(line),
+                column: Some(column),
+                message,
+            } => write!(f, "{path}:{line}:{column}: {message}"),
+            LoadErrorKind::Invalid { message, .. } => write!(f, "{path}: {message}"),
+        }
+    }
+}
+
+/// Kind of load error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadErrorKind {
+    /// File does not exist.
+    NotFound,
+
+    /// File could not be read.
+    FailedToRead(String),
+
+    /// File could not be written.
+    FailedToWrite(String),
+
+    /// File contents are invalid.
+    Invalid {
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+}
+
+/// Semantic problem in loaded skill definitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadWarning {
+    /// Expected hits exceed total hits.
+    ExpectedAboveHits {
+        id: u32,
+        expected: usize,
+        hits: usize,
+    },
+
+    /// Hit id is claimed by multiple skills.
+    DuplicateHitId { hit_id: u32, id: u32, other: u32 },
+
+    /// Disabled entry does not match any earlier definition.
+    DisabledUnknown { id: u32 },
+
+    /// Buff id is a condition id, the definition is ignored.
+    BuffIsCondition {
+        id: u32,
+        buff_id: u32,
+        condition: Condition,
+    },
+}
+
+impl LoadWarning {
+    /// Checks custom definitions on top of the given base definitions for semantic problems.
+    pub fn check(base: &[SkillDef], custom: &[SkillDef]) -> Vec<Self> {
+        let mut warnings = Vec::new();
+
+        // hit ids of base definitions not replaced by custom definitions
+        let mut claimed = HashMap::new();
+        for def in base
+            .iter()
+            .filter(|def| def.enabled && !custom.iter().any(|other| other.id == def.id))
+        {
+            for hit_id in &def.hit_ids {
+                claimed.insert(*hit_id, def.id);
+            }
+        }
+
+        let mut seen: Vec<u32> = base.iter().map(|def| def.id).collect();
+        for def in custom {
+            if !def.enabled {
+                if !seen.contains(&def.id) {
+                    warnings.push(Self::DisabledUnknown { id: def.id });
+                }
+                continue;
+            }
+            seen.push(def.id);
+
+            if let (Some(hits), Some(expected)) = (def.hits, def.expected) {
+                if expected > hits {
+                    warnings.push(Self::ExpectedAboveHits {
+                        id: def.id,
+                        expected,
+                        hits,
+                    });
+                }
+            }
+
+            for hit_id in &def.hit_ids {
+                if let Some(other) = claimed.insert(*hit_id, def.id) {
+                    if other != def.id {
+                        warnings.push(Self::DuplicateHitId {
+                            hit_id: *hit_id,
+                            id: def.id,
+                            other,
+                        });
+                    }
+                }
+            }
+        }
+
+        warnings
+    }
+
+    /// Checks buff definitions for semantic problems.
+    ///
+    /// Definitions shadowing conditions would stop transfer tracking for the condition.
+    pub fn check_buffs(buffs: &[BuffDef]) -> Vec<Self> {
+        buffs
+            .iter()
+            .filter(|def| def.enabled)
+            .flat_map(|def| {
+                iter::once(def.id)
+                    .chain(def.alt_ids.iter().copied())
+                    .filter_map(|buff_id| {
+                        Condition::try_from(buff_id)
+                            .ok()
+                            .map(|condition| Self::BuffIsCondition {
+                                id: def.id,
+                                buff_id,
+                                condition,
+                            })
+                    })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for LoadWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ExpectedAboveHits { id, expected, hits } => {
+                write!(f, "skill {id}: expected {expected} above hits {hits}")
+            }
+            Self::DuplicateHitId { hit_id, id, other } => {
+                write!(
+                    f,
+                    "skill {id}: hit id {hit_id} already used by skill {other}"
+                )
+            }
+            Self::DisabledUnknown { id } => {
+                write!(f, "skill {id}: disabled entry matches no default")
+            }
+            Self::BuffIsCondition {
+                id,
+                buff_id,
+                condition,
+            } => {
+                write!(
+                    f,
+                    "buff {id}: id {buff_id} is condition {}, definition ignored",
+                    condition.as_ref()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::StackingType;
+
+    fn def(id: u32, hit_ids: &[u32]) -> SkillDef {
+        SkillDef {
+            hit_ids: hit_ids.into(),
+            ..SkillDef::new(id)
+        }
+    }
+
+    #[test]
+    fn expected_above_hits() {
+        let custom = [SkillDef {
+            hits: Some(2),
+            expected: Some(3),
+            ..SkillDef::new(1)
+        }];
+        assert_eq!(
+            LoadWarning::check(&[], &custom),
+            [LoadWarning::ExpectedAboveHits {
+                id: 1,
+                expected: 3,
+                hits: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn duplicate_hit_id() {
+        let base = [def(1, &[10]), def(2, &[20])];
+
+        // replaced base definitions release their hit ids
+        let custom = [def(1, &[]), def(3, &[10, 20])];
+        assert_eq!(
+            LoadWarning::check(&base, &custom),
+            [LoadWarning::DuplicateHitId {
+                hit_id: 20,
+                id: 3,
+                other: 2
+            }]
+        );
+
+        // within custom definitions
+        let custom = [def(4, &[30]), def(5, &[30])];
+        assert_eq!(
+            LoadWarning::check(&[], &custom),
+            [LoadWarning::DuplicateHitId {
+                hit_id: 30,
+                id: 5,
+                other: 4
+            }]
+        );
+    }
+
+    #[test]
+    fn disabled_unknown() {
+        let disabled = |id| SkillDef {
+            enabled: false,
+            ..SkillDef::new(id)
+        };
+        let base = [def(1, &[])];
+        let custom = [disabled(1), def(2, &[]), disabled(2), disabled(3)];
+        assert_eq!(
+            LoadWarning::check(&base, &custom),
+            [LoadWarning::DisabledUnknown { id: 3 }]
+        );
+    }
+
+    #[test]
+    fn buff_is_condition() {
+        let buff = |id, alt_ids: &[u32]| BuffDef {
+            id,
+            alt_ids: alt_ids.into(),
+            name: "Test".into(),
+            enabled: true,
+            category: Default::default(),
+            stacking: StackingType::Intensity,
+            stacks: 25,
+        };
+        let buffs = [buff(740, &[]), buff(1, &[736])];
+        assert_eq!(
+            LoadWarning::check_buffs(&buffs),
+            [LoadWarning::BuffIsCondition {
+                id: 1,
+                buff_id: 736,
+                condition: Condition::Bleeding
+            }]
+        );
+    }
+
+    #[test]
+    fn valid() {
+        let base = [def(1, &[10])];
+        let custom = [def(1, &[10, 11]), def(2, &[20])];
+        assert!(LoadWarning::check(&base, &custom).is_empty());
+    }
+}
@@ -1,57 +1,141 @@
 mod buff;
+mod buff_def;
+mod build;
 mod condi;
+mod load;
 mod skill_def;
 mod skill_info;
 mod skill_names;
 
-pub use self::{buff::*, condi::*, skill_def::*, skill_info::*, skill_names::*};
+pub use self::{
+    buff::*, buff_def::*, build::*, condi::*, load::*, skill_def::*, skill_info::*, skill_names::*,
+};
 
+use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
-    fs::File,
-    io::{self, BufReader},
-    path::Path,
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
 };
+use strum::{AsRefStr, EnumString, VariantArray, VariantNames};
 
 /// Skill data.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SkillData {
+    /// All skill definitions.
+    defs: Vec<SkillDef>,
+
+    /// Build to restrict active definitions to.
+    build: Option<Build>,
+
     /// Mapping of skill ids to data index.
     map: HashMap<u32, usize>,
 
+    /// Mapping of buff ids to data index.
+    buffs: HashMap<u32, usize>,
+
     /// Skill information data.
     data: Vec<SkillInfo>,
 }
 
 impl SkillData {
     /// Creates new skill data with the given data.
+    ///
+    /// Later definitions replace earlier definitions with the same id entirely, including their hit ids.
+    /// Disabled definitions remove earlier definitions with the same id.
     pub fn new(skills: impl IntoIterator<Item = SkillDef>) -> Self {
-        let iter = skills.into_iter();
-        let (size, _) = iter.size_hint();
+        let mut data = Self {
+            defs: skills.into_iter().collect(),
+            build: None,
+            map: HashMap::new(),
+            buffs: HashMap::new(),
+            data: Vec::new(),
+        };
+        data.update();
+        data
+    }
+
+    /// Returns the build active definitions are restricted to.
+    pub fn build(&self) -> Option<Build> {
+        self.build
+    }
+
+    /// Restricts active definitions to the given build.
+    ///
+    /// Definitions for other professions or specializations are ignored entirely.
+    /// Without a build all definitions are active.
+    pub fn set_build(&mut self, build: Option<Build>) {
+        if build != self.build {
+            self.build = build;
+            self.update();
+        }
+    }
+
+    /// Checks whether the definition is active for the build.
+    ///
+    /// Without a build all definitions are active.
+    fn build_matches(build: Option<Build>, def: &SkillDef) -> bool {
+        match build {
+            Some(build) => build.matches(def),
+            None => true,
+        }
+    }
+
+    /// Updates the active definitions.
+    fn update(&mut self) {
+        let build = self.build;
+        let iter = self
+            .defs
+            .iter()
+            .filter(|def| Self::build_matches(build, def));
+        let size = self.defs.len();
         let mut data = Vec::<SkillInfo>::with_capacity(size);
-        let mut map = HashMap::with_capacity(size);
+        let mut map = HashMap::<u32, usize>::with_capacity(size);
+        let mut buffs = HashMap::new();
 
         for skill in iter {
+            // check for previous definition with same primary id
+            if let Some(index) = map.get(&skill.id).copied() {
+                if data[index].id == skill.id {
+                    // remove other hit ids pointing at same index
+                    map.retain(|_, i| *i != index);
+                    buffs.retain(|_, i| *i != index);
+                }
+            }
+
             if skill.enabled {
                 let index = data.len();
                 map.insert(skill.id, index);
                 for hit_id in &skill.hit_ids {
                     map.insert(*hit_id, index);
                 }
-                data.push(skill.into());
-            } else if let Some(index) = map.remove(&skill.id) {
-                // check if primary id
-                if data[index].id == skill.id {
-                    // remove other hit ids pointing at same index
-                    map.retain(|_, i| *i != index);
+                if let Some(buff_id) = skill.buff_id {
+                    buffs.insert(buff_id, index);
                 }
+                data.push(skill.clone().into());
+            } else {
+                map.remove(&skill.id);
             }
         }
 
         data.shrink_to_fit();
         map.shrink_to_fit();
+        buffs.shrink_to_fit();
+
+        self.map = map;
+        self.buffs = buffs;
+        self.data = data;
+    }
+
+    /// Returns the number of active definitions.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
 
-        Self { map, data }
+    /// Checks whether there are no active definitions.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
     }
 
     /// Creates new skill data with the defaults.
@@ -59,6 +143,15 @@ impl SkillData {
         Self::new(Self::iter_defaults())
     }
 
+    /// Creates new skill data from the given source.
+    pub fn from_source(source: DataSource, custom: impl IntoIterator<Item = SkillDef>) -> Self {
+        match source {
+            DataSource::Defaults => Self::with_defaults(),
+            DataSource::Custom => Self::new(custom),
+            DataSource::Merged => Self::new(Self::iter_defaults().chain(custom)),
+        }
+    }
+
     /// Returns an iterator over the defaults.
     fn iter_defaults() -> impl Iterator<Item = SkillDef> {
         include!(concat!(env!("OUT_DIR"), "/skills.rs")).into_iter()
@@ -71,6 +164,13 @@ impl SkillData {
         self.map.contains_key(&id)
     }
 
+    /// Returns an iterator over all skill ids with an entry.
+    ///
+    /// This includes hit ids.
+    pub fn ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.map.keys().copied()
+    }
+
     /// Retrieves the [`SkillInfo`] corresponding to the skill id.
     ///
     /// This includes disabled definitions.
@@ -78,34 +178,199 @@ impl SkillData {
         self.map.get(&id).and_then(|index| self.data.get(*index))
     }
 
+    /// Returns an iterator over the skill name overrides of active definitions.
+    pub fn names(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.map.iter().filter_map(|(id, index)| {
+            let info = &self.data[*index];
+            match &info.name {
+                Some(name) if info.id == *id => Some((*id, name.as_str())),
+                _ => None,
+            }
+        })
+    }
+
+    /// Retrieves the [`SkillDef`] of the active definition for the skill id.
+    pub fn get_def(&self, id: u32) -> Option<&SkillDef> {
+        let info = self.get(id)?;
+        self.defs
+            .iter()
+            .rev()
+            .find(|def| def.id == info.id && def.enabled && Self::build_matches(self.build, def))
+    }
+
+    /// Retrieves the [`SkillInfo`] tracking applications of the buff id.
+    pub fn get_by_buff(&self, buff: u32) -> Option<&SkillInfo> {
+        self.buffs
+            .get(&buff)
+            .and_then(|index| self.data.get(*index))
+    }
+
+    /// Attempts to read definitions from a given file path.
+    pub fn read_file(path: impl AsRef<Path>) -> Result<Vec<SkillDef>, LoadError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|err| LoadError::from_io(path, err))?;
+        serde_yaml::from_reader(BufReader::new(file)).map_err(|err| LoadError::from_yaml(path, err))
+    }
+
+    /// Attempts to write definitions to a given file path.
+    ///
+    /// Files with a `.json` extension are written as JSON, other files as YAML.
+    pub fn write_file(path: impl AsRef<Path>, defs: &[SkillDef]) -> Result<(), LoadError> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|err| LoadError::from_write_io(path, err))?;
+        let writer = BufWriter::new(file);
+        if matches!(path.extension().and_then(|ext| ext.to_str()), Some("json")) {
+            serde_json::to_writer_pretty(writer, defs)
+                .map_err(|err| LoadError::new(path, LoadErrorKind::FailedToWrite(err.to_string())))
+        } else {
+            serde_yaml::to_writer(writer, defs).map_err(|err| LoadError::from_yaml(path, err))
+        }
+    }
+
+    /// Attempts to save definitions as overrides to a given file path.
+    ///
+    /// Existing entries with the same id are replaced, other entries are appended.
+    pub fn save_overrides(
+        path: impl AsRef<Path>,
+        overrides: impl IntoIterator<Item = SkillDef>,
+    ) -> Result<(), LoadError> {
+        let path = path.as_ref();
+        let mut defs = match Self::read_file(path) {
+            Ok(defs) => defs,
+            Err(LoadError {
+                kind: LoadErrorKind::NotFound,
+                ..
+            }) => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        for def in overrides {
+            match defs.iter_mut().rev().find(|other| other.id == def.id) {
+                Some(other) => *other = def,
+                None => defs.push(def),
+            }
+        }
+        Self::write_file(path, &defs)
+    }
+
+    /// Returns the definition files in a given directory, sorted by file name.
+    ///
+    /// Supports `.yml`, `.yaml` and `.json` files.
+    pub fn dir_files(path: impl AsRef<Path>) -> Result<Vec<PathBuf>, LoadError> {
+        let path = path.as_ref();
+        let mut files = fs::read_dir(path)
+            .map_err(|err| LoadError::from_io(path, err))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file()
+                    && matches!(
+                        path.extension().and_then(|ext| ext.to_str()),
+                        Some("json" | "yml" | "yaml")
+                    )
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        Ok(files)
+    }
+
+    /// Attempts to read definitions from all files in a given directory.
+    ///
+    /// Files are read in order of their name.
+    pub fn read_dir(path: impl AsRef<Path>) -> Result<Vec<SkillDef>, LoadError> {
+        let mut data = Vec::new();
+        for file in Self::dir_files(path)? {
+            data.extend(Self::read_file(file)?);
+        }
+        Ok(data)
+    }
+
+    /// Checks custom definitions for semantic problems when used with the given source.
+    pub fn check(source: DataSource, custom: &[SkillDef]) -> Vec<LoadWarning> {
+        let base = match source {
+            DataSource::Merged => Self::iter_defaults().collect(),
+            DataSource::Defaults | DataSource::Custom => Vec::new(),
+        };
+        LoadWarning::check(&base, custom)
+    }
+
     /// Attempts to load data from a given file path.
-    pub fn try_load(&mut self, path: impl AsRef<Path>) -> Result<usize, LoadError> {
-        let file = BufReader::new(File::open(path)?);
-        let data: Vec<SkillDef> = serde_yaml::from_reader(file)?;
+    pub fn try_load(
+        &mut self,
+        path: impl AsRef<Path>,
+        source: DataSource,
+    ) -> Result<usize, LoadError> {
+        let data = Self::read_file(path)?;
         let count = data.len();
-        *self = Self::new(Self::iter_defaults().chain(data));
+        *self = Self::from_source(source, data);
         Ok(count)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum LoadError {
-    NotFound,
-    FailedToRead,
-    Invalid,
+impl SkillDef {
+    /// Creates a new enabled definition without additional information.
+    pub const fn new(id: u32) -> Self {
+        Self {
+            id,
+            name: None,
+            enabled: true,
+            hit_ids: Vec::new(),
+            hits: None,
+            expected: None,
+            max_duration: None,
+            minion: false,
+            buff_id: None,
+            buff_cast: false,
+            buff_hits: false,
+            profession: None,
+            spec: None,
+        }
+    }
 }
 
-impl From<io::Error> for LoadError {
-    fn from(err: io::Error) -> Self {
-        match err.kind() {
-            io::ErrorKind::NotFound => Self::NotFound,
-            _ => Self::FailedToRead,
-        }
+/// Source of skill definitions.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    VariantNames,
+    VariantArray,
+    AsRefStr,
+    EnumString,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum DataSource {
+    /// Only the default definitions.
+    Defaults,
+
+    /// Only the custom definitions.
+    Custom,
+
+    /// Default definitions overwritten by custom definitions.
+    #[default]
+    Merged,
+}
+
+impl DataSource {
+    /// Checks whether custom definitions are used.
+    pub fn uses_custom(&self) -> bool {
+        !matches!(self, Self::Defaults)
     }
 }
 
-impl From<serde_yaml::Error> for LoadError {
-    fn from(_: serde_yaml::Error) -> Self {
-        Self::Invalid
+impl From<usize> for DataSource {
+    fn from(value: usize) -> Self {
+        match value {
+            0 => Self::Defaults,
+            1 => Self::Custom,
+            2 => Self::Merged,
+            _ => Self::default(),
+        }
     }
 }
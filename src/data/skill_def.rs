@@ -1,37 +1,69 @@
+use evtc::{Profession, Specialization};
 use serde::{Deserialize, Serialize};
 
-// TODO: allow name override?
-// TODO: instead of hits allow counting buff apply?
-// TODO: support instant casts with buff apply?
-
 /// Skill definition parsed from a file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillDef {
     /// Skill id.
     pub id: u32,
 
+    /// Skill name overriding the name from combat.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
     /// Whether the definition is active.
-    #[serde(default = "default_as_true")]
+    #[serde(default = "default_as_true", skip_serializing_if = "is_true")]
     pub enabled: bool,
 
     /// Additional hit skill ids.
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub hit_ids: Vec<u32>,
 
     /// Total amount of hits.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub hits: Option<usize>,
 
     /// Minimum amount of hits expected.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub expected: Option<usize>,
 
     /// Maximum duration (ms) to count as one cast.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub max_duration: Option<i32>,
 
     /// Whether to include minion hits.
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub minion: bool,
+
+    /// Buff id applied by the skill.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buff_id: Option<u32>,
+
+    /// Whether own buff applications count as cast.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub buff_cast: bool,
+
+    /// Whether own buff applications count as hit per target.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub buff_hits: bool,
+
+    /// Profession the definition is restricted to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profession: Option<Profession>,
+
+    /// Elite specialization the definition is restricted to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spec: Option<Specialization>,
 }
 
 fn default_as_true() -> bool {
     true
 }
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
@@ -1,4 +1,5 @@
 use super::SkillDef;
+use strum::AsRefStr;
 
 /// Extra error margin for max duration.
 const DURATION_EPSILON: i32 = 500;
@@ -9,6 +10,9 @@ pub struct SkillInfo {
     /// Skill id.
     pub id: u32,
 
+    /// Skill name override.
+    pub name: Option<String>,
+
     /// Hit information.
     pub hits: Option<SkillHits>,
 
@@ -17,21 +21,31 @@ pub struct SkillInfo {
 
     /// Whether to include minion hits.
     pub minion: bool,
+
+    /// Buff application information.
+    pub buff: Option<SkillBuff>,
 }
 
 impl From<SkillDef> for SkillInfo {
     fn from(def: SkillDef) -> Self {
         let SkillDef {
             id,
+            name,
             enabled: _,
             hit_ids: _,
             hits,
             expected,
             max_duration,
             minion,
+            buff_id,
+            buff_cast,
+            buff_hits,
+            profession: _,
+            spec: _,
         } = def;
         Self {
             id,
+            name,
             hits: hits.map(|max| SkillHits {
                 max,
                 expected: expected.unwrap_or((max + 1) / 2),
@@ -40,10 +54,34 @@ impl From<SkillDef> for SkillInfo {
                 .map(|dur| dur + DURATION_EPSILON)
                 .unwrap_or(i32::MAX),
             minion,
+            buff: buff_id.map(|id| SkillBuff {
+                id,
+                cast: buff_cast,
+                hits: buff_hits,
+                window: max_duration
+                    .map(|dur| dur + DURATION_EPSILON)
+                    .unwrap_or(DURATION_EPSILON),
+            }),
         }
     }
 }
 
+/// Skill buff application information.
+#[derive(Debug, Clone)]
+pub struct SkillBuff {
+    /// Buff id.
+    pub id: u32,
+
+    /// Whether own applications count as cast.
+    pub cast: bool,
+
+    /// Whether own applications count as hit per target.
+    pub hits: bool,
+
+    /// Maximum time (ms) between applications to count as one cast.
+    pub window: i32,
+}
+
 /// Skill hit information.
 #[derive(Debug, Clone)]
 pub struct SkillHits {
@@ -87,7 +125,7 @@ impl SkillHits {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, AsRefStr)]
 pub enum SkillHitCount {
     Miss,
     Expected,
@@ -1,7 +1,8 @@
-use arcdps::Agent;
+use evtc::Agent;
+use serde::{Deserialize, Serialize};
 
 /// A fight in the history.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fight<T> {
     /// Fight target species.
     pub target: Option<u32>,
@@ -15,6 +16,14 @@ pub struct Fight<T> {
     /// End time of the fight.
     pub end: Option<u64>,
 
+    /// Whether the fight was imported and no longer receives events.
+    #[serde(default)]
+    pub imported: bool,
+
+    /// Whether the fight is kept in the history until removed.
+    #[serde(default)]
+    pub pinned: bool,
+
     /// Associated fight data.
     pub data: T,
 }
@@ -31,6 +40,8 @@ impl<T> Fight<T> {
             name: None,
             start,
             end: None,
+            imported: false,
+            pinned: false,
             data,
         }
     }
@@ -84,8 +95,11 @@ impl<T> Fight<T> {
     }
 
     /// Calculates the timestamp as relative time to the fight start.
+    ///
+    /// Imported fights do not accept any timestamps.
     pub fn relative_time(&self, time: u64) -> Option<i32> {
         match self.end {
+            _ if self.imported => None,
             Some(end) if time > end => None,
             _ => match time.checked_sub(self.start) {
                 Some(rel) => rel.try_into().ok(),
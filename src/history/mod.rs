@@ -1,11 +1,14 @@
 mod fight;
+mod persist;
 mod settings;
+#[cfg(feature = "plugin")]
 mod ui;
 
 pub use self::fight::*;
+pub use self::persist::*;
 pub use self::settings::*;
 
-use arcdps::Agent;
+use evtc::Agent;
 use std::collections::VecDeque;
 
 /// History of fights.
@@ -14,6 +17,8 @@ pub struct History<T> {
     pub settings: HistorySettings,
     viewed: usize,
     fights: VecDeque<Fight<T>>,
+    export: Option<ExportFormat>,
+    revision: u64,
 }
 
 #[allow(unused)]
@@ -24,6 +29,8 @@ impl<T> History<T> {
             settings: HistorySettings::new(max_fights, min_duration, discard_at_end),
             viewed: 0,
             fights: VecDeque::new(),
+            export: None,
+            revision: 0,
         }
     }
 
@@ -44,6 +51,7 @@ impl<T> History<T> {
 
     /// Returns a mutable reference to the latest fight.
     pub fn latest_fight_mut(&mut self) -> Option<&mut Fight<T>> {
+        self.changed();
         self.fights.front_mut()
     }
 
@@ -54,14 +62,26 @@ impl<T> History<T> {
 
     /// Returns a mutable reference to the fight at the given index.
     pub fn fight_at_mut(&mut self, index: usize) -> Option<&mut Fight<T>> {
+        self.changed();
         self.fights.get_mut(index)
     }
 
     /// Returns an iterator over all fights.
-    pub fn all_fights(&self) -> impl Iterator<Item = &Fight<T>> {
+    pub fn all_fights(&self) -> impl DoubleEndedIterator<Item = &Fight<T>> {
         self.fights.iter()
     }
 
+    /// Returns a mutable iterator over all fights.
+    pub fn all_fights_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Fight<T>> {
+        self.changed();
+        self.fights.iter_mut()
+    }
+
+    /// Converts the history into its fights, latest first.
+    pub fn into_fights(self) -> VecDeque<Fight<T>> {
+        self.fights
+    }
+
     /// Returns the index of the currently viewed fight.
     pub fn viewed(&self) -> usize {
         self.viewed
@@ -77,6 +97,23 @@ impl<T> History<T> {
         self.fight_at_mut(self.viewed)
     }
 
+    /// Returns the current revision of the fights.
+    ///
+    /// The revision changes whenever the fights may have been modified.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Marks the fights as changed.
+    fn changed(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+    }
+
+    /// Takes the pending export request for the currently viewed fight.
+    pub fn take_export(&mut self) -> Option<ExportFormat> {
+        self.export.take()
+    }
+
     /// Updates the viewed index when a fight is added/removed.
     fn update_viewed(&mut self, change: isize) {
         if self.viewed > 0 {
@@ -87,32 +124,88 @@ impl<T> History<T> {
         }
     }
 
-    /// Calculates relative time to start for the latest fight.
+    /// Returns the index of the fight the timestamp belongs to.
+    ///
+    /// Timestamps within a fight are attributed to it, even if the fight already ended.
+    /// Timestamps between two fights are attributed to the following fight.
+    /// Imported fights are skipped.
+    fn fight_index(&self, time: u64) -> Option<usize> {
+        let mut following = None;
+        for (index, fight) in self.fights.iter().enumerate() {
+            if fight.imported {
+                continue;
+            }
+            if time >= fight.start {
+                return match fight.end {
+                    Some(end) if time > end => following,
+                    _ => Some(index),
+                };
+            }
+            following = Some(index);
+        }
+        following
+    }
+
+    /// Calculates relative time to start for the fight the timestamp belongs to.
     pub fn relative_time(&self, time: u64) -> Option<i32> {
-        // TODO: handle timestamp in previous fight
-        self.latest_fight()
-            .and_then(|fight| fight.relative_time(time))
+        self.fight_index(time)
+            .and_then(|index| self.fights[index].relative_time(time))
     }
 
-    /// Returns the latest fight and the relative time to fight start.
+    /// Returns the fight the timestamp belongs to and the relative time to fight start.
     pub fn fight_and_time(&mut self, time: u64) -> Option<(i32, &mut Fight<T>)> {
-        // TODO: handle timestamp in previous fight
-        self.latest_fight_mut()
-            .and_then(|fight| fight.relative_time(time).map(|time| (time, fight)))
+        self.changed();
+        self.fight_index(time).and_then(|index| {
+            let fight = &mut self.fights[index];
+            fight.relative_time(time).map(|time| (time, fight))
+        })
     }
 
     /// Adds a fight to the history.
+    ///
+    /// Pinned fights are never discarded.
     pub fn add_fight(&mut self, fight: Fight<T>) {
         if let Some(prev) = self.fights.front() {
-            if matches!(prev.duration(), Some(duration) if duration < self.settings.min_duration) {
+            if !prev.pinned
+                && matches!(prev.duration(), Some(duration) if duration < self.settings.min_duration)
+            {
                 self.fights.pop_front();
             }
         }
-        if self.len() > self.settings.max_fights {
-            self.fights.pop_back();
-        }
+        self.trim(self.settings.max_fights);
         self.fights.push_front(fight);
         self.update_viewed(1);
+        self.changed();
+    }
+
+    /// Removes the oldest unpinned fights until at most the given amount is left.
+    fn trim(&mut self, max_fights: usize) {
+        while self.fights.iter().filter(|fight| !fight.pinned).count() > max_fights {
+            if let Some(index) = self.fights.iter().rposition(|fight| !fight.pinned) {
+                self.remove_fight(index);
+            }
+        }
+    }
+
+    /// Removes the fight at the given index.
+    pub fn remove_fight(&mut self, index: usize) -> Option<Fight<T>> {
+        let fight = self.fights.remove(index)?;
+        self.changed();
+        if index < self.viewed {
+            self.viewed -= 1;
+        }
+        if self.viewed >= self.len() {
+            self.viewed = 0;
+        }
+        Some(fight)
+    }
+
+    /// Toggles whether the fight at the given index is pinned.
+    pub fn toggle_pin(&mut self, index: usize) {
+        if let Some(fight) = self.fights.get_mut(index) {
+            fight.pinned = !fight.pinned;
+            self.changed();
+        }
     }
 
     /// Adds a fight with default data to the history.
@@ -150,10 +243,96 @@ impl<T> History<T> {
     pub fn end_latest_fight(&mut self, time: u64) {
         if let Some(fight @ Fight { end: None, .. }) = self.latest_fight_mut() {
             let duration = fight.end(time);
-            if self.settings.discard_at_end && duration < self.settings.min_duration {
+            let pinned = fight.pinned;
+            if self.settings.discard_at_end && !pinned && duration < self.settings.min_duration {
                 self.fights.pop_front();
                 self.update_viewed(-1);
             }
         }
     }
 }
+
+/// Format to export a fight in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Single JSON file with the full fight.
+    Json,
+
+    /// One CSV file per log.
+    Csv,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> History<()> {
+        let mut history = History::new(10, 0, false);
+        history.add_fight(Fight::new(1000, ()));
+        history.end_latest_fight(2000);
+        history.add_fight(Fight::new(3000, ()));
+        history.end_latest_fight(4000);
+        history
+    }
+
+    #[test]
+    fn fight_index() {
+        let history = history();
+        assert_eq!(history.fight_index(3500), Some(0));
+        assert_eq!(history.fight_index(1500), Some(1));
+
+        // boundaries belong to the fight
+        assert_eq!(history.fight_index(1000), Some(1));
+        assert_eq!(history.fight_index(2000), Some(1));
+        assert_eq!(history.fight_index(4000), Some(0));
+    }
+
+    #[test]
+    fn fight_index_gap() {
+        let history = history();
+
+        // between fights is attributed to the following fight
+        assert_eq!(history.fight_index(2500), Some(0));
+
+        // before the first fight is attributed to the first fight
+        assert_eq!(history.fight_index(500), Some(1));
+
+        // after the latest fight ended there is no fight
+        assert_eq!(history.fight_index(4500), None);
+    }
+
+    #[test]
+    fn fight_index_ongoing() {
+        let mut history = history();
+        history.add_fight(Fight::new(5000, ()));
+        assert_eq!(history.fight_index(9000), Some(0));
+    }
+
+    #[test]
+    fn revision() {
+        let mut history = history();
+        let revision = history.revision();
+        history.fight_and_time(1500);
+        assert_ne!(history.revision(), revision);
+
+        let revision = history.revision();
+        let _ = history.all_fights().count();
+        assert_eq!(history.revision(), revision);
+        history.remove_fight(0);
+        assert_ne!(history.revision(), revision);
+    }
+
+    #[test]
+    fn fight_index_imported() {
+        let mut history = history();
+        let mut imported = Fight::new(10000, ());
+        imported.end = Some(20000);
+        imported.imported = true;
+        history.add_fight(imported);
+
+        // imported fights are skipped
+        assert_eq!(history.fight_index(15000), None);
+        assert_eq!(history.fight_index(3500), Some(1));
+        assert_eq!(history.fight_index(2500), Some(1));
+    }
+}
@@ -0,0 +1,174 @@
+use super::{Fight, History};
+use log::{debug, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Sender},
+    thread::{self, JoinHandle},
+};
+
+/// Current version of the history file format.
+///
+/// Files of older versions are still loaded, fields added since default to empty.
+/// Version 2 stores buffs by id instead of name.
+pub const HISTORY_VERSION: u32 = 2;
+
+/// Version header of a history file.
+#[derive(Debug, Deserialize)]
+struct Header {
+    version: u32,
+}
+
+/// History file contents.
+#[derive(Debug, Serialize, Deserialize)]
+struct HistoryFile<F> {
+    version: u32,
+    fights: Vec<F>,
+}
+
+impl<T> History<T> {
+    /// Saves all fights to a file at the given path.
+    ///
+    /// See [`write_file`] for how the file is replaced.
+    pub fn save_file(&self, path: impl AsRef<Path>) -> Result<(), PersistError>
+    where
+        T: Serialize,
+    {
+        write_file(path, &self.to_bytes()?)
+    }
+
+    /// Serializes all fights into the contents of a history file.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PersistError>
+    where
+        T: Serialize,
+    {
+        let contents = HistoryFile {
+            version: HISTORY_VERSION,
+            fights: self.fights.iter().collect(),
+        };
+        serde_json::to_vec(&contents).map_err(|_| PersistError::FailedToWrite)
+    }
+
+    /// Loads fights from a file at the given path, returning the amount of fights loaded.
+    ///
+    /// Loaded fights are added as imported fights behind the existing fights.
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<usize, PersistError>
+    where
+        T: DeserializeOwned,
+    {
+        let contents = fs::read_to_string(path)?;
+        let Header { version } =
+            serde_json::from_str(&contents).map_err(|_| PersistError::Invalid)?;
+        if version > HISTORY_VERSION {
+            return Err(PersistError::UnsupportedVersion(version));
+        }
+
+        let file: HistoryFile<Fight<T>> =
+            serde_json::from_str(&contents).map_err(|_| PersistError::Invalid)?;
+        let count = file.fights.len();
+        for mut fight in file.fights {
+            // timestamps of previous sessions are meaningless
+            fight.imported = true;
+            self.fights.push_back(fight);
+        }
+        self.trim(self.settings.max_fights);
+        self.changed();
+        Ok(count)
+    }
+}
+
+/// Writes history file contents to the given path.
+///
+/// The contents are written to a temporary file first, which then replaces the file.
+/// This way the previous file is kept intact if writing is interrupted.
+pub fn write_file(path: impl AsRef<Path>, contents: &[u8]) -> Result<(), PersistError> {
+    let path = path.as_ref();
+    let temp = path.with_extension("tmp");
+    let mut file = File::create(&temp).map_err(|_| PersistError::FailedToWrite)?;
+    file.write_all(contents)
+        .and_then(|_| file.sync_all())
+        .map_err(|_| PersistError::FailedToWrite)?;
+    fs::rename(&temp, path).map_err(|_| PersistError::FailedToWrite)
+}
+
+/// Writes history files on a background thread.
+///
+/// Files are written in the order they were requested.
+#[derive(Debug)]
+pub struct BackgroundSave {
+    sender: Sender<(PathBuf, Vec<u8>)>,
+    thread: JoinHandle<()>,
+}
+
+impl BackgroundSave {
+    /// Spawns a new background thread for writing.
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel::<(PathBuf, Vec<u8>)>();
+        let thread = thread::spawn(move || {
+            for (path, contents) in receiver {
+                match write_file(&path, &contents) {
+                    Ok(()) => debug!("Saved fight history to \"{}\"", path.display()),
+                    Err(err) => warn!("Failed to save fight history: {err:?}"),
+                }
+            }
+        });
+        Self { sender, thread }
+    }
+
+    /// Requests the contents to be written to the given path.
+    pub fn save(&self, path: PathBuf, contents: Vec<u8>) {
+        // thread only exits once the sender is dropped
+        let _ = self.sender.send((path, contents));
+    }
+
+    /// Waits for all requested writes to finish.
+    pub fn finish(self) {
+        drop(self.sender);
+        let _ = self.thread.join();
+    }
+}
+
+/// Error while persisting the history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PersistError {
+    NotFound,
+    FailedToRead,
+    FailedToWrite,
+    Invalid,
+    UnsupportedVersion(u32),
+}
+
+impl From<io::Error> for PersistError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => Self::NotFound,
+            _ => Self::FailedToRead,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    #[test]
+    fn background_save() {
+        let path = env::temp_dir().join(format!("arcdps_buddy_test_{}.json", process::id()));
+        let mut history = History::<()>::new(10, 0, false);
+        let save = BackgroundSave::spawn();
+        save.save(path.clone(), history.to_bytes().unwrap());
+        history.add_fight(Fight::new(1000, ()));
+        save.save(path.clone(), history.to_bytes().unwrap());
+        save.finish();
+
+        // latest request is written last
+        let mut loaded = History::<()>::new(10, 0, false);
+        let result = loaded.load_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(result, Ok(1));
+        assert!(!path.with_extension("tmp").exists());
+    }
+}
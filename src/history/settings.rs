@@ -1,6 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "plugin")]
 use super::History;
+#[cfg(feature = "plugin")]
 use arc_util::settings::HasSettings;
-use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistorySettings {
@@ -24,6 +27,7 @@ impl HistorySettings {
     }
 }
 
+#[cfg(feature = "plugin")]
 impl<T> HasSettings for History<T> {
     type Settings = HistorySettings;
 
@@ -1,4 +1,4 @@
-use super::History;
+use super::{ExportFormat, History};
 use arc_util::colors::GREY;
 use arcdps::{
     exports::{self, CoreColor},
@@ -13,6 +13,8 @@ impl<T> History<T> {
             ui.text("No history");
         } else {
             let grey = colors.core(CoreColor::MediumGrey).unwrap_or(GREY);
+            let mut pin = None;
+            let mut remove = None;
             for (i, fight) in self.fights.iter().enumerate() {
                 // TODO: display log start time
                 let name = fight.name.as_deref().unwrap_or("Unknown");
@@ -29,6 +31,38 @@ impl<T> History<T> {
                         self.viewed = i;
                     }
                 }
+
+                ui.same_line();
+                let label = if fight.pinned { "Unpin" } else { "Pin" };
+                if ui.small_button(format!("{label}##pin{i}")) {
+                    pin = Some(i);
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Pinned fights are kept until removed");
+                }
+                ui.same_line();
+                if ui.small_button(format!("Remove##remove{i}")) {
+                    remove = Some(i);
+                }
+            }
+            if let Some(index) = pin {
+                self.toggle_pin(index);
+            }
+            if let Some(index) = remove {
+                self.remove_fight(index);
+            }
+
+            ui.separator();
+            ui.menu("Export", || {
+                if ui.menu_item("JSON") {
+                    self.export = Some(ExportFormat::Json);
+                }
+                if ui.menu_item("CSV") {
+                    self.export = Some(ExportFormat::Csv);
+                }
+            });
+            if ui.is_item_hovered() {
+                ui.tooltip_text("Export the viewed fight");
             }
         }
     }
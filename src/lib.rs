@@ -1,12 +1,18 @@
-mod combat;
-mod data;
-mod history;
+pub mod combat;
+pub mod data;
+pub mod history;
+#[cfg(feature = "plugin")]
 mod plugin;
+pub mod processor;
+pub mod replay;
+#[cfg(feature = "plugin")]
 mod ui;
 
+#[cfg(feature = "plugin")]
 use plugin::Plugin;
 
 // create exports for arcdps
+#[cfg(feature = "plugin")]
 arcdps::export! {
     name: "Buddy",
     sig: 0x84c13713,
@@ -16,6 +22,7 @@ arcdps::export! {
     },
     release: || Plugin::lock().unload(),
     combat: Plugin::area_event,
+    combat_local: Plugin::local_event,
     imgui: Plugin::render,
     options_end: |ui| Plugin::lock().render_settings(ui),
     options_windows: Plugin::render_window_options,
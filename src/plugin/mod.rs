@@ -2,12 +2,15 @@ pub mod event;
 pub mod ui;
 
 use crate::{
-    combat::{player::Player, skill::SkillMap, CombatData},
-    data::{LoadError, SkillData},
-    history::History,
+    combat::{export, skill::SkillMap},
+    data::{BuffData, LoadError, LoadErrorKind, LoadWarning, SkillData, SkillDef},
+    history::{BackgroundSave, ExportFormat, History, PersistError},
+    processor::{EventQueue, Processor},
+    replay::{EvtcFile, ReplayError},
     ui::{
-        breakbar_log::BreakbarLog, buff_log::BuffLog, cast_log::CastLog, multi_view::MultiView,
-        transfer_log::TransferLog,
+        breakbar_log::BreakbarLog, buff_log::BuffLog, cast_log::CastLog,
+        damage_summary::DamageSummary, discovery::SkillDiscovery, multi_view::MultiView,
+        transfer_log::TransferLog, tuner::DefinitionTuner, uptime::UptimeTable,
     },
 };
 use arc_util::{
@@ -18,7 +21,15 @@ use arc_util::{
 use log::{info, warn};
 use once_cell::sync::Lazy;
 use semver::Version;
-use std::sync::{Mutex, MutexGuard};
+use std::{
+    fs, mem,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard,
+    },
+    time::{Duration, Instant, SystemTime},
+};
 
 /// Plugin version.
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -29,28 +40,67 @@ const SETTINGS_FILE: &str = "arcdps_buddy.json";
 /// Cast skill definition file name.
 const SKILLS_FILE: &str = "arcdps_buddy_skills.yml";
 
+/// Custom buff definition file name.
+const BUFFS_FILE: &str = "arcdps_buddy_buffs.yml";
+
+/// Fight history file name.
+const HISTORY_FILE: &str = "arcdps_buddy_history.json";
+
+/// Skill name cache file name.
+const SKILL_CACHE_FILE: &str = "arcdps_buddy_skill_cache.json";
+
+/// Skill API dump file name.
+const SKILL_API_FILE: &str = "arcdps_buddy_api_skills.json";
+
+/// Fight export directory name.
+const EXPORT_DIR: &str = "arcdps_buddy_exports";
+
+/// Cast skill definition directory name.
+const SKILLS_DIR: &str = "arcdps_buddy_skills";
+
+/// Interval between checks for changes to the skill definition file.
+const SKILLS_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Main plugin instance.
-// FIXME: a single mutex for the whole thing is potentially inefficient
 static PLUGIN: Lazy<Mutex<Plugin>> = Lazy::new(|| Mutex::new(Plugin::new()));
 
+/// Combat events waiting to be processed by the plugin.
+static EVENTS: Lazy<EventQueue> = Lazy::new(EventQueue::new);
+
+/// Whether local combat events are queued, readable without the plugin lock.
+static LOCAL_HITS: AtomicBool = AtomicBool::new(false);
+
 /// Main plugin.
 #[derive(Debug)]
 pub struct Plugin {
     updater: Updater,
 
-    skills: SkillMap,
-    data: SkillData,
-    data_state: Result<usize, LoadError>,
+    processor: Processor,
+    data_state: Vec<(PathBuf, Result<usize, LoadError>)>,
+    data_warnings: Vec<LoadWarning>,
+    data_modified: Vec<(PathBuf, Option<SystemTime>)>,
+    data_checked: Option<Instant>,
+    buff_state: Option<Result<usize, LoadError>>,
+    buff_warnings: Vec<LoadWarning>,
+    save_error: Option<String>,
+
+    history_saved: Option<u64>,
+    history_save: Option<BackgroundSave>,
+
+    api_state: Option<Result<usize, PersistError>>,
 
-    self_instance_id: Option<u16>,
-    players: Vec<Player>,
-    history: History<CombatData>,
+    replay_path: String,
+    replay_state: Option<Result<usize, ReplayError>>,
 
     multi_view: Window<MultiView>,
     cast_log: Window<CastLog>,
     buff_log: Window<BuffLog>,
     breakbar_log: Window<BreakbarLog>,
     transfer_log: Window<TransferLog>,
+    damage_summary: Window<DamageSummary>,
+    uptime_table: Window<UptimeTable>,
+    tuner: Window<DefinitionTuner>,
+    discovery: Window<SkillDiscovery>,
 }
 
 impl Plugin {
@@ -69,13 +119,25 @@ impl Plugin {
                 VERSION.parse().unwrap(),
             ),
 
-            skills: SkillMap::new(),
-            data: SkillData::with_defaults(),
-            data_state: Err(LoadError::NotFound),
+            processor: Processor::new(
+                SkillMap::new(),
+                SkillData::with_defaults(),
+                History::new(10, 5000, true),
+            ),
+            data_state: Vec::new(),
+            data_warnings: Vec::new(),
+            data_modified: Vec::new(),
+            data_checked: None,
+
+            history_saved: None,
+            history_save: None,
+            api_state: None,
+            buff_state: None,
+            buff_warnings: Vec::new(),
+            save_error: None,
 
-            self_instance_id: None,
-            players: Vec::new(),
-            history: History::new(10, 5000, true),
+            replay_path: String::new(),
+            replay_state: None,
 
             multi_view: Window::with_default("Buddy Multi", options.clone()),
             cast_log: Window::with_default("Buddy Casts", options.clone()),
@@ -89,6 +151,10 @@ impl Plugin {
                 },
             ),
             transfer_log: Window::with_default("Buddy Transfer", options.clone()),
+            damage_summary: Window::with_default("Buddy Damage", options.clone()),
+            uptime_table: Window::with_default("Buddy Uptime", options.clone()),
+            tuner: Window::with_default("Buddy Tuner", options.clone()),
+            discovery: Window::with_default("Buddy Discovery", options.clone()),
         }
     }
 
@@ -112,49 +178,385 @@ impl Plugin {
             }
         );
 
-        settings.load_component(&mut self.history);
+        settings.load_component(&mut self.processor);
+        settings.load_component(&mut self.processor.history);
         settings.load_component(&mut self.multi_view);
         settings.load_component(&mut self.cast_log);
         settings.load_component(&mut self.buff_log);
         settings.load_component(&mut self.breakbar_log);
+        settings.load_component(&mut self.damage_summary);
+        settings.load_component(&mut self.uptime_table);
+        settings.load_component(&mut self.tuner);
+        settings.load_component(&mut self.discovery);
+        self.update_local_hits();
 
         self.load_data();
+        self.load_buffs();
+        self.load_history();
+        self.load_skill_cache();
+        self.import_skills();
+    }
+
+    /// Updates whether local combat events are queued from the settings.
+    pub fn update_local_hits(&self) {
+        LOCAL_HITS.store(self.processor.settings.local_hits, Ordering::Relaxed);
+    }
+
+    /// Imports skill names and icons from the API dump file.
+    pub fn import_skills(&mut self) {
+        if let Some(path) = Settings::config_path(SKILL_API_FILE) {
+            let result = self.processor.skills.import_api(&path);
+            match result {
+                Ok(count) => info!("Imported {count} skills from \"{}\"", path.display()),
+                Err(PersistError::NotFound) => {}
+                Err(err) => warn!("Failed to import skills: {err:?}"),
+            }
+            self.api_state = Some(result);
+        }
+    }
+
+    /// Loads the skill name cache from the previous session.
+    pub fn load_skill_cache(&mut self) {
+        if let Some(path) = Settings::config_path(SKILL_CACHE_FILE) {
+            match self.processor.skills.load_file(&path) {
+                Ok(count) => info!("Loaded {count} skill names from \"{}\"", path.display()),
+                Err(PersistError::NotFound) => {}
+                Err(err) => warn!("Failed to load skill cache: {err:?}"),
+            }
+        }
+    }
+
+    /// Saves the skill name cache for the next session.
+    pub fn save_skill_cache(&self) {
+        if let Some(path) = Settings::config_path(SKILL_CACHE_FILE) {
+            if let Err(err) = self.processor.skills.save_file(&path) {
+                warn!("Failed to save skill cache: {err:?}");
+            }
+        }
+    }
+
+    /// Loads the fight history from the previous session.
+    pub fn load_history(&mut self) {
+        if let Some(path) = Settings::config_path(HISTORY_FILE) {
+            match self.processor.history.load_file(&path) {
+                Ok(count) => info!("Loaded {count} fights from \"{}\"", path.display()),
+                Err(PersistError::NotFound) => {}
+                Err(err) => warn!("Failed to load fight history: {err:?}"),
+            }
+        }
+    }
+
+    /// Saves the fight history for the next session.
+    ///
+    /// The file is written on a background thread to avoid blocking the render thread.
+    pub fn save_history(&mut self) {
+        if let Some(path) = Settings::config_path(HISTORY_FILE) {
+            match self.processor.history.to_bytes() {
+                Ok(contents) => self
+                    .history_save
+                    .get_or_insert_with(BackgroundSave::spawn)
+                    .save(path, contents),
+                Err(err) => warn!("Failed to save fight history: {err:?}"),
+            }
+        }
+    }
+
+    /// Waits for pending fight history saves to finish.
+    pub fn finish_history_save(&mut self) {
+        if let Some(save) = self.history_save.take() {
+            save.finish();
+        }
+    }
+
+    /// Exports the currently viewed fight to the export directory.
+    pub fn export_fight(&mut self, format: ExportFormat) {
+        let Processor {
+            skills,
+            buff_data,
+            history,
+            ..
+        } = &mut self.processor;
+        let (Some(fight), Some(dir)) = (history.viewed_fight(), Settings::config_path(EXPORT_DIR))
+        else {
+            return;
+        };
+        if let Err(err) = fs::create_dir_all(&dir) {
+            warn!("Failed to create export directory: {err}");
+            return;
+        }
+        match format {
+            ExportFormat::Json => match export::export_json(fight, skills, buff_data, &dir) {
+                Ok(path) => info!("Exported fight to \"{}\"", path.display()),
+                Err(err) => warn!("Failed to export fight: {err}"),
+            },
+            ExportFormat::Csv => match export::export_csv(fight, skills, buff_data, &dir) {
+                Ok(paths) => info!(
+                    "Exported fight to {} files in \"{}\"",
+                    paths.len(),
+                    dir.display()
+                ),
+                Err(err) => warn!("Failed to export fight: {err}"),
+            },
+        }
     }
 
     pub fn load_data(&mut self) {
+        let source = self.processor.settings.data_source;
+        let mut custom = Vec::new();
+        self.data_state.clear();
+        self.data_warnings.clear();
+        self.data_modified.clear();
+        if source.uses_custom() {
+            let files = Self::data_files();
+            self.data_modified = Self::modified_times(&files);
+            for file in files {
+                let path = match file {
+                    Ok(path) => path,
+                    Err(err) => {
+                        warn!("Failed to list custom definitions: {err}");
+                        self.data_state.push((err.path.clone(), Err(err)));
+                        continue;
+                    }
+                };
+                let result = SkillData::read_file(&path).map(|data| {
+                    let count = data.len();
+                    custom.extend(data);
+                    count
+                });
+                match &result {
+                    Ok(_) => info!("Loaded custom definitions from \"{}\"", path.display()),
+                    Err(err) => warn!("Failed to load custom definitions: {err}"),
+                }
+                self.data_state.push((path, result));
+            }
+
+            // keep last good data
+            if self.data_state.iter().any(|(_, result)| result.is_err()) {
+                return;
+            }
+
+            self.data_warnings = SkillData::check(source, &custom);
+            for warning in &self.data_warnings {
+                warn!("Custom definitions: {warning}");
+            }
+        }
+        self.processor
+            .set_data(SkillData::from_source(source, custom));
+    }
+
+    /// Returns the custom definition files in load order.
+    ///
+    /// The single file is loaded first, followed by the directory files in order of their name.
+    fn data_files() -> Vec<Result<PathBuf, LoadError>> {
+        let mut files = Vec::new();
         if let Some(path) = Settings::config_path(SKILLS_FILE) {
             if path.exists() {
-                self.data_state = self.data.try_load(&path);
-
-                if self.data_state.is_ok() {
-                    info!("Loaded custom definitions from \"{}\"", path.display());
-                } else {
-                    warn!(
-                        "Failed to load custom definitions from \"{}\"",
-                        path.display()
-                    );
+                files.push(Ok(path));
+            }
+        }
+        if let Some(path) = Settings::config_path(SKILLS_DIR) {
+            if path.exists() {
+                match SkillData::dir_files(&path) {
+                    Ok(dir_files) => files.extend(dir_files.into_iter().map(Ok)),
+                    Err(err) => files.push(Err(err)),
                 }
             }
         }
+        files
+    }
+
+    /// Reloads the custom definitions if any file changed.
+    pub fn check_data_file(&mut self) {
+        if !self.processor.settings.data_source.uses_custom() {
+            return;
+        }
+
+        let now = Instant::now();
+        if let Some(checked) = self.data_checked {
+            if now.duration_since(checked) < SKILLS_CHECK_INTERVAL {
+                return;
+            }
+        }
+        self.data_checked = Some(now);
+
+        if Self::modified_times(&Self::data_files()) != self.data_modified {
+            info!("Custom definitions changed, reloading");
+            self.load_data();
+        }
+    }
+
+    /// Returns the modification times of the given files.
+    fn modified_times(files: &[Result<PathBuf, LoadError>]) -> Vec<(PathBuf, Option<SystemTime>)> {
+        files
+            .iter()
+            .flatten()
+            .map(|path| {
+                let modified = fs::metadata(path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok();
+                (path.clone(), modified)
+            })
+            .collect()
+    }
+
+    /// Saves definitions as overrides to the custom definition files.
+    ///
+    /// Each definition is saved to the last file defining its id, so it is not overridden by a later file.
+    /// Definitions without an existing entry are saved to the single custom definition file.
+    pub fn save_overrides(&mut self, defs: Vec<SkillDef>) {
+        self.save_error = None;
+        if !self.processor.settings.data_source.uses_custom() {
+            warn!("Not saving overrides, custom definitions are not used");
+            self.save_error =
+                Some("Custom definitions are not used with the current data source".into());
+            return;
+        }
+        let Some(single) = Settings::config_path(SKILLS_FILE) else {
+            return;
+        };
+
+        let mut files = Vec::new();
+        for file in Self::data_files() {
+            match file.and_then(|path| SkillData::read_file(&path).map(|defs| (path, defs))) {
+                Ok(file) => files.push(file),
+                Err(err) => {
+                    warn!("Not saving overrides, failed to read custom definitions: {err}");
+                    self.save_error = Some(format!("Failed to read {err}"));
+                    return;
+                }
+            }
+        }
+
+        let mut targets: Vec<(PathBuf, Vec<SkillDef>)> = Vec::new();
+        for def in defs {
+            let path = files
+                .iter()
+                .rev()
+                .find(|(_, defs)| defs.iter().any(|other| other.id == def.id))
+                .map_or(&single, |(path, _)| path);
+            match targets.iter_mut().find(|(target, _)| target == path) {
+                Some((_, defs)) => defs.push(def),
+                None => targets.push((path.clone(), vec![def])),
+            }
+        }
+
+        for (path, defs) in targets {
+            let ids = defs.iter().map(|def| def.id).collect::<Vec<_>>();
+            match SkillData::save_overrides(&path, defs) {
+                Ok(()) => info!("Saved overrides for {ids:?} to \"{}\"", path.display()),
+                Err(err) => {
+                    warn!("Failed to save overrides: {err}");
+                    self.save_error = Some(format!("Failed to save {err}"));
+                }
+            }
+        }
+        self.load_data();
+    }
+
+    /// Loads buff definitions from the custom buff definition file.
+    pub fn load_buffs(&mut self) {
+        let source = self.processor.settings.data_source;
+        self.buff_state = None;
+        self.buff_warnings.clear();
+        let mut custom = Vec::new();
+        if source.uses_custom() {
+            if let Some(path) = Settings::config_path(BUFFS_FILE) {
+                let result = BuffData::read_file(&path).map(|data| {
+                    let count = data.len();
+                    custom = data;
+                    count
+                });
+                match &result {
+                    Ok(_) => info!("Loaded custom buffs from \"{}\"", path.display()),
+                    Err(err) => warn!("Failed to load custom buffs: {err}"),
+                }
+
+                // keep last good data
+                let failed = matches!(&result, Err(err) if err.kind != LoadErrorKind::NotFound);
+                self.buff_state = Some(result);
+                if failed {
+                    return;
+                }
+
+                self.buff_warnings = LoadWarning::check_buffs(&custom);
+                for warning in &self.buff_warnings {
+                    warn!("Custom buffs: {warning}");
+                }
+            }
+        }
+        self.processor
+            .set_buff_data(BuffData::from_source(source, custom));
     }
 
     pub fn reset_data(&mut self) {
-        self.data = SkillData::with_defaults();
-        self.data_state = Err(LoadError::NotFound);
+        self.processor.set_data(SkillData::with_defaults());
+        self.processor.set_buff_data(BuffData::with_defaults());
+        self.buff_state = None;
+        self.buff_warnings.clear();
+        self.data_state.clear();
+        self.data_warnings.clear();
+
+        // skip hot reload until a file changes again
+        self.data_modified = Self::modified_times(&Self::data_files());
+    }
+
+    /// Replays the log file at the given path into the history.
+    pub fn load_replay(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let result = EvtcFile::from_path(path).map(|log| self.replay(&log));
+        match &result {
+            Ok(count) => info!("Replayed {count} fights from \"{}\"", path.display()),
+            Err(err) => warn!("Failed to replay log \"{}\": {err:?}", path.display()),
+        }
+        self.replay_state = Some(result);
+    }
+
+    /// Replays a parsed log, adding its fights to the history.
+    ///
+    /// Returns the number of fights added.
+    pub fn replay(&mut self, log: &EvtcFile) -> usize {
+        let skills = mem::take(&mut self.processor.skills);
+        let mut replay = Processor::new(
+            skills,
+            self.processor.data.clone(),
+            History::new(usize::MAX, 0, false),
+        );
+        replay.buff_data = self.processor.buff_data.clone();
+        replay.replay(log);
+        self.processor.skills = replay.skills;
+
+        let fights = replay.history.into_fights();
+        let count = fights.len();
+        for mut fight in fights.into_iter().rev() {
+            fight.imported = true;
+            self.processor.history.add_fight(fight);
+        }
+        count
     }
 
     /// Unloads the plugin.
     pub fn unload(&mut self) {
+        self.process_all_events();
+
         let mut settings = Settings::from_file(SETTINGS_FILE);
 
         settings.store_data("version", VERSION);
-        settings.store_component(&self.history);
+        settings.store_component(&self.processor);
+        settings.store_component(&self.processor.history);
         settings.store_component(&self.multi_view);
         settings.store_component(&self.cast_log);
         settings.store_component(&self.buff_log);
         settings.store_component(&self.breakbar_log);
+        settings.store_component(&self.damage_summary);
+        settings.store_component(&self.uptime_table);
+        settings.store_component(&self.tuner);
+        settings.store_component(&self.discovery);
 
         settings.save_file();
+
+        self.save_history();
+        self.finish_history_save();
+        self.save_skill_cache();
     }
 }
 
@@ -1,10 +1,14 @@
 use super::Plugin;
 use crate::{
-    combat::skill::SkillMap,
-    data::LoadError,
+    data::LoadErrorKind,
+    history::PersistError,
+    processor::Processor,
+    replay::ReplayError,
     ui::{
         breakbar_log::BreakbarLogProps, buff_log::BuffLogProps, cast_log::CastLogProps,
-        multi_view::MultiViewProps, transfer_log::TransferLogProps,
+        damage_summary::DamageSummaryProps, discovery::SkillDiscoveryProps,
+        multi_view::MultiViewProps, transfer_log::TransferLogProps, tuner::DefinitionTunerProps,
+        uptime::UptimeTableProps,
     },
 };
 use arc_util::{
@@ -19,20 +23,27 @@ use arcdps::{
 impl Plugin {
     /// Callback for standalone UI creation.
     pub fn render(ui: &Ui, not_loading: bool) {
+        let mut plugin = Self::lock();
+        plugin.process_events();
+        plugin.check_data_file();
+
         let ui_settings = exports::ui_settings();
         if !ui_settings.hidden && (not_loading || ui_settings.draw_always) {
-            Self::lock().render_windows(ui)
+            plugin.render_windows(ui)
         }
     }
 
     /// Renders standalone UI windows.
     pub fn render_windows(&mut self, ui: &Ui) {
-        let Plugin {
+        let Processor {
+            settings,
             skills,
             data,
+            buff_data,
             history,
+            discovery,
             ..
-        } = self;
+        } = &mut self.processor;
 
         self.updater.render(ui);
 
@@ -41,6 +52,7 @@ impl Plugin {
             MultiViewProps {
                 skills,
                 data,
+                buff_data,
                 history,
             },
         );
@@ -52,10 +64,45 @@ impl Plugin {
                 history,
             },
         );
-        self.buff_log.render(ui, BuffLogProps { history });
+        self.buff_log
+            .render(ui, BuffLogProps { buff_data, history });
         self.breakbar_log
             .render(ui, BreakbarLogProps { skills, history });
         self.transfer_log.render(ui, TransferLogProps { history });
+        self.damage_summary
+            .render(ui, DamageSummaryProps { skills, history });
+        self.uptime_table
+            .render(ui, UptimeTableProps { buff_data, history });
+        self.tuner.render(
+            ui,
+            DefinitionTunerProps {
+                skills,
+                data,
+                history,
+                save_error: self.save_error.as_deref(),
+            },
+        );
+
+        self.discovery.render(
+            ui,
+            SkillDiscoveryProps {
+                skills,
+                data,
+                discovery,
+                enabled: settings.discovery,
+                save_error: self.save_error.as_deref(),
+            },
+        );
+
+        let mut saves = self.tuner.take_saves();
+        saves.extend(self.discovery.take_saves());
+        if !saves.is_empty() {
+            self.save_overrides(saves);
+        }
+
+        if let Some(format) = self.processor.history.take_export() {
+            self.export_fight(format);
+        }
     }
 
     /// Renders settings UI.
@@ -99,13 +146,48 @@ impl Plugin {
             "Transfer",
             &mut self.transfer_log.options.hotkey,
         );
+        render::input_key(
+            ui,
+            "##damage-key",
+            "Damage",
+            &mut self.damage_summary.options.hotkey,
+        );
+        render::input_key(
+            ui,
+            "##uptime-key",
+            "Uptime",
+            &mut self.uptime_table.options.hotkey,
+        );
+        render::input_key(ui, "##tuner-key", "Tuner", &mut self.tuner.options.hotkey);
+        render::input_key(
+            ui,
+            "##discovery-key",
+            "Discovery",
+            &mut self.discovery.options.hotkey,
+        );
+
+        ui.spacing();
+        ui.spacing();
+
+        ui.text_colored(grey, "Combat");
+        if ui.checkbox("Local hits", &mut self.processor.settings.local_hits) {
+            self.update_local_hits();
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Additionally use local combat events for cast hits");
+        }
+
+        ui.checkbox("Skill discovery", &mut self.processor.settings.discovery);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Record own skills without definition");
+        }
 
         ui.spacing();
         ui.spacing();
 
         ui.text_colored(grey, "Fight history");
         let input_width = 100.0;
-        let settings = &mut self.history.settings;
+        let settings = &mut self.processor.history.settings;
 
         let mut max_fights = settings.max_fights as _;
         ui.set_next_item_width(input_width);
@@ -143,18 +225,91 @@ impl Plugin {
         ui.spacing();
         ui.spacing();
 
-        // TODO: select data, default only, custom only or both
         ui.text_colored(grey, "Custom data");
+        ui.set_next_item_width(input_width);
+        if render::enum_combo_array(ui, "Source", &mut self.processor.settings.data_source) {
+            self.load_data();
+            self.load_buffs();
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Use default definitions, custom definitions or both\nCustom definitions overwrite defaults with the same id");
+        }
+        let count = self.processor.data.len();
+        ui.text("Build:");
+        ui.same_line();
+        match self.processor.data.build() {
+            Some(build) => ui.text(format!("{build}, {count} active definitions")),
+            None => ui.text_colored(grey, format!("Unknown, {count} active definitions")),
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text(
+                "Only definitions for the current profession & elite specialization are active",
+            );
+        }
         ui.text("Status:");
+        if !self.processor.settings.data_source.uses_custom() {
+            ui.same_line();
+            ui.text_colored(grey, "Disabled");
+        } else if self.data_state.is_empty() {
+            ui.same_line();
+            ui.text_colored(yellow, "Not found");
+        }
+        for (path, result) in &self.data_state {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default();
+            ui.text(name);
+            ui.same_line();
+            match result {
+                Ok(count) => ui.text_colored(green, format!("Loaded {count} entries")),
+                Err(err) => {
+                    match &err.kind {
+                        LoadErrorKind::NotFound => ui.text_colored(yellow, "Not found"),
+                        LoadErrorKind::FailedToRead(_) => {
+                            ui.text_colored(red, "Failed to read file")
+                        }
+                        LoadErrorKind::FailedToWrite(_) => {
+                            ui.text_colored(red, "Failed to write file")
+                        }
+                        LoadErrorKind::Invalid { .. } => ui.text_colored(red, "Failed to parse"),
+                    }
+                    ui.text_wrapped(err.to_string());
+                }
+            }
+        }
+        if !self.data_warnings.is_empty() {
+            ui.text_colored(yellow, format!("{} warnings", self.data_warnings.len()));
+            for warning in &self.data_warnings {
+                ui.text_wrapped(warning.to_string());
+            }
+        }
+        ui.text("Buffs:");
         ui.same_line();
-        match self.data_state {
-            Ok(count) => ui.text_colored(green, format!("Loaded {count} entries")),
-            Err(LoadError::NotFound) => ui.text_colored(yellow, "Not found"),
-            Err(LoadError::FailedToRead) => ui.text_colored(red, "Failed to read file"),
-            Err(LoadError::Invalid) => ui.text_colored(red, "Failed to parse"),
+        let buff_count = self.processor.buff_data.len();
+        match &self.buff_state {
+            Some(Ok(count)) => ui.text_colored(
+                green,
+                format!("Loaded {count} entries, {buff_count} active definitions"),
+            ),
+            Some(Err(err)) if err.kind != LoadErrorKind::NotFound => {
+                ui.text_colored(red, "Failed to load");
+                ui.text_wrapped(err.to_string());
+            }
+            _ => ui.text(format!("{buff_count} active definitions")),
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Custom buff definitions from arcdps_buddy_buffs.yml");
+        }
+        if !self.buff_warnings.is_empty() {
+            ui.text_colored(yellow, format!("{} warnings", self.buff_warnings.len()));
+            for warning in &self.buff_warnings {
+                ui.text_wrapped(warning.to_string());
+            }
         }
         if ui.button("Reload##data") {
             self.load_data();
+            self.load_buffs();
         }
         ui.same_line_with_spacing(0.0, 5.0);
         if ui.button("Reset##data") {
@@ -164,13 +319,84 @@ impl Plugin {
         ui.spacing();
         ui.spacing();
 
+        ui.text_colored(grey, "Log replay");
+        ui.input_text("##replay-path", &mut self.replay_path)
+            .hint("Path to .evtc/.zevtc")
+            .build();
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Recorded log to add to the fight history");
+        }
+        let in_fight =
+            matches!(self.processor.history.latest_fight(), Some(fight) if !fight.ended());
+        if in_fight {
+            ui.text_colored(grey, "Unavailable during fight");
+        } else if ui.button("Replay##replay") {
+            let path = self.replay_path.trim().to_owned();
+            self.load_replay(path);
+        }
+        if let Some(state) = self.replay_state {
+            ui.text("Status:");
+            ui.same_line();
+            match state {
+                Ok(count) => ui.text_colored(green, format!("Replayed {count} fights")),
+                Err(ReplayError::NotFound) => ui.text_colored(yellow, "Not found"),
+                Err(ReplayError::FailedToRead) => ui.text_colored(red, "Failed to read file"),
+                Err(ReplayError::Invalid) => ui.text_colored(red, "Failed to parse"),
+                Err(ReplayError::UnsupportedRevision(revision)) => {
+                    ui.text_colored(red, format!("Unsupported revision {revision}"))
+                }
+            }
+        }
+
+        ui.spacing();
+        ui.spacing();
+
         ui.text_colored(grey, "Skill cache");
-        ui.text(format!("Overrides: {}", SkillMap::overrides()));
-        ui.text(format!("Cached: {}", self.skills.cached()));
+        ui.text(format!("Overrides: {}", self.processor.skills.overrides()));
+        ui.text(format!("Cached: {}", self.processor.skills.cached()));
+        match self.processor.skills.version() {
+            Some(build) => ui.text(format!("Game build: {build}")),
+            None => ui.text_colored(grey, "Game build: Unknown"),
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Cached names are saved across sessions\nThey are discarded when the game build changes");
+        }
         if ui.button("Reset##skills") {
-            self.skills.reset();
+            self.processor.skills.reset();
             log::info!("reset skill cache");
         }
+
+        ui.spacing();
+        ui.spacing();
+
+        ui.text_colored(grey, "Skill import");
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Skill names & icons from a GW2 API /v2/skills JSON file");
+        }
+        ui.text("Status:");
+        ui.same_line();
+        match self.api_state {
+            Some(Ok(count)) => ui.text_colored(green, format!("Imported {count} skills")),
+            Some(Err(PersistError::NotFound)) | None => ui.text_colored(yellow, "Not found"),
+            Some(Err(PersistError::Invalid)) => ui.text_colored(red, "Failed to parse"),
+            Some(Err(_)) => ui.text_colored(red, "Failed to read file"),
+        }
+        let skills = &self.processor.skills;
+        ui.text(format!(
+            "Names: {}, Icons: {}",
+            skills.imported(),
+            skills.imported_icons()
+        ));
+        let (covered, total) = skills.import_coverage();
+        ui.text(format!("Known skills covered: {covered}/{total}"));
+        let (covered, total) = skills.coverage(self.processor.data.ids());
+        ui.text(format!("Definitions covered: {covered}/{total}"));
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Includes hit ids of definitions");
+        }
+        if ui.button("Import##api") {
+            self.import_skills();
+        }
     }
 
     /// Renders window checkboxes.
@@ -182,6 +408,10 @@ impl Plugin {
             ui.checkbox("Buddy Buffs", plugin.buff_log.visible_mut());
             ui.checkbox("Buddy Breakbar", plugin.breakbar_log.visible_mut());
             ui.checkbox("Buddy Transfer", plugin.transfer_log.visible_mut());
+            ui.checkbox("Buddy Damage", plugin.damage_summary.visible_mut());
+            ui.checkbox("Buddy Uptime", plugin.uptime_table.visible_mut());
+            ui.checkbox("Buddy Tuner", plugin.tuner.visible_mut());
+            ui.checkbox("Buddy Discovery", plugin.discovery.visible_mut());
         }
         false
     }
@@ -195,6 +425,10 @@ impl Plugin {
                 buff_log,
                 breakbar_log,
                 transfer_log,
+                damage_summary,
+                uptime_table,
+                tuner,
+                discovery,
                 ..
             } = &mut *Self::lock();
 
@@ -204,6 +438,10 @@ impl Plugin {
                 && !buff_log.options.key_press(key)
                 && !breakbar_log.options.key_press(key)
                 && !transfer_log.options.key_press(key)
+                && !damage_summary.options.key_press(key)
+                && !uptime_table.options.key_press(key)
+                && !tuner.options.key_press(key)
+                && !discovery.options.key_press(key)
         } else {
             true
         }
@@ -0,0 +1,433 @@
+use super::{EventSource, Processor};
+use crate::{
+    combat::{
+        breakbar::BreakbarHit,
+        buff::{Buff, BuffApply, Stacking},
+        cast::{Cast, CastState, Hit, HitOutcome},
+        player::Player,
+        transfer::{Apply, Condition, Remove},
+    },
+    data::Build,
+};
+use evtc::{Activation, Agent, BuffRemove, Event, EventCategory, StateChange, Strike};
+use log::debug;
+
+impl Processor {
+    /// Handles a combat event from area stats.
+    pub fn area_event(
+        &mut self,
+        event: Option<&Event>,
+        src: Option<&Agent>,
+        dst: Option<&Agent>,
+        skill_name: Option<&str>,
+    ) {
+        if let Some(src) = src {
+            if let Some(event) = event {
+                let src_self = src.is_self != 0;
+                match event.categorize() {
+                    EventCategory::StateChange => match event.get_statechange() {
+                        StateChange::GWBuild => self.skills.set_version(event.src_agent),
+                        StateChange::SquadCombatStart => self.start_fight(event, dst),
+                        StateChange::LogNPCUpdate => self.fight_target(event, dst),
+                        StateChange::SquadCombatEnd => self.end_fight(event, dst),
+                        _ => {}
+                    },
+
+                    EventCategory::Activation if src_self && self.data.contains(event.skill_id) => {
+                        match event.get_activation() {
+                            Activation::Start => self.cast_start(event, skill_name),
+                            Activation::CancelFire
+                            | Activation::CancelCancel
+                            | Activation::Reset => self.cast_end(event, skill_name),
+                            _ => {}
+                        }
+                    }
+
+                    EventCategory::Activation if src_self && self.settings.discovery => {
+                        if let Activation::Start = event.get_activation() {
+                            self.skills.try_register(event.skill_id, skill_name);
+                            self.discovery.cast(event.skill_id);
+                        }
+                    }
+
+                    EventCategory::BuffApply => {
+                        if let Some(dst) = dst {
+                            let buff = event.skill_id;
+                            if self.data.get_by_buff(buff).is_some() && event.is_offcycle == 0 {
+                                self.buff_cast(event, src, dst);
+                            }
+
+                            if let Some(def) = self.buff_data.get(buff) {
+                                let (buff, stacking) = (def.buff(), def.stacking());
+                                self.apply_buff(event, buff, stacking, src, dst)
+                            } else if let Ok(condi) = buff.try_into() {
+                                // only care about condi applies from self to other and ignore extensions
+                                if src_self && dst.is_self == 0 && event.is_offcycle == 0 {
+                                    self.apply_condi(event, condi, dst)
+                                }
+                            }
+                        }
+                    }
+
+                    EventCategory::BuffRemove => {
+                        // removes have the agent losing the buff as source
+                        if let Some(def) = self.buff_data.get(event.skill_id) {
+                            let buff = def.buff();
+                            self.remove_buff_stacks(event, buff, src)
+                        }
+
+                        if let Some(dst) = dst {
+                            // only care about removes from self to self
+                            if event.get_buffremove() == BuffRemove::Manual
+                                && src_self
+                                && dst.is_self != 0
+                            {
+                                if let Ok(condi) = event.skill_id.try_into() {
+                                    self.remove_buff(event, condi)
+                                }
+                            }
+                        }
+                    }
+
+                    EventCategory::Strike => {
+                        if let Some(dst) = dst {
+                            self.strike(event, skill_name, src, dst)
+                        }
+                    }
+
+                    _ => {}
+                }
+            } else if let Some(dst) = dst {
+                // check for tracking change
+                if src.elite == 0 {
+                    if src.prof != 0 {
+                        // player added, replacing a previous entry for the same agent
+                        let player = Player::from_tracking_change(src, dst);
+                        if dst.is_self != 0 {
+                            self.self_instance_id = Some(player.instance_id);
+                            debug!("own instance id changed to {}", player.instance_id);
+                            self.set_build(Some(Build::new(player.prof, player.spec)));
+                        }
+                        self.players.retain(|other| other.id != player.id);
+                        self.players.push(player);
+                    } else if let Some(pos) =
+                        self.players.iter().position(|player| player.id == src.id)
+                    {
+                        // player tracked & removed
+                        self.players.swap_remove(pos);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Handles a combat event from local stats.
+    ///
+    /// Only own damage hits are used, if enabled.
+    pub fn local_event(
+        &mut self,
+        event: Option<&Event>,
+        src: Option<&Agent>,
+        dst: Option<&Agent>,
+        skill_name: Option<&str>,
+    ) {
+        if !self.settings.local_hits {
+            return;
+        }
+        if let (Some(event), Some(src), Some(dst)) = (event, src, dst) {
+            if let EventCategory::Strike = event.categorize() {
+                let is_minion = self.is_own_minion(event);
+                let is_own = src.is_self != 0 || is_minion;
+                if is_own {
+                    if let Some(outcome) = HitOutcome::from_strike(event.get_strike()) {
+                        self.skills.try_register(event.skill_id, skill_name);
+                        self.own_hit(event, EventSource::Local, outcome, is_minion, dst)
+                    }
+                }
+            }
+        }
+    }
+
+    fn get_master(&self, event: &Event) -> Option<&crate::combat::player::Player> {
+        self.players
+            .iter()
+            .find(|player| player.instance_id == event.src_master_instance_id)
+    }
+
+    fn is_own_minion(&self, event: &Event) -> bool {
+        match self.self_instance_id {
+            Some(id) => event.src_master_instance_id == id,
+            None => false,
+        }
+    }
+
+    /// Flushes simulated buff coverage into the fight at the given time.
+    pub fn flush_buffs(&mut self, time: u64) {
+        let coverage = self.buffs.flush(time);
+        if let Some((_, fight)) = self.history.fight_and_time(time) {
+            let uptime = &mut fight.data.uptime;
+            uptime.updated = uptime.updated.max(time);
+            for (agent, buff, coverage) in coverage {
+                // only squad members are relevant for uptime
+                if let Some(player) = self.players.iter().find(|player| player.id == agent) {
+                    uptime.add(player, buff, coverage);
+                }
+            }
+        }
+    }
+
+    fn start_fight(&mut self, event: &Event, target: Option<&Agent>) {
+        let species = event.src_agent as u32;
+        debug!("log start for {species}, {target:?}");
+        self.flush_buffs(event.time);
+        self.history
+            .add_fight_with_target(event.time, species, target);
+    }
+
+    fn fight_target(&mut self, event: &Event, target: Option<&Agent>) {
+        let species = event.src_agent as u32;
+        debug!("log target change to {species}, {target:?}");
+        self.history
+            .update_fight_target(event.time, species, target);
+    }
+
+    fn end_fight(&mut self, event: &Event, target: Option<&Agent>) {
+        let species = event.src_agent;
+        debug!("log end for {species}, {target:?}");
+        self.flush_buffs(event.time);
+        self.discovery.complete();
+        self.history.end_latest_fight(event.time);
+    }
+
+    fn cast_start(&mut self, event: &Event, skill_name: Option<&str>) {
+        let id = event.skill_id;
+        let skill = self.skills.try_register(id, skill_name);
+        debug!("start {skill:?}");
+        if let Some((time, fight)) = self.history.fight_and_time(event.time) {
+            let cast = Cast::from_start(time, id, CastState::Casting);
+            fight.data.add_cast(cast);
+        }
+    }
+
+    fn cast_end(&mut self, event: &Event, skill_name: Option<&str>) {
+        let state = event.get_activation().into();
+        let duration = event.value;
+        let id = event.skill_id;
+        self.skills.try_register(id, skill_name);
+
+        // cast end may arrive after the fight ended, attribute it via the cast start
+        let start = event.time.saturating_sub(duration.max(0) as u64);
+        if let Some((start, fight)) = self.history.fight_and_time(start) {
+            let data = &mut fight.data;
+            if let Some(cast) = data.latest_cast_mut(id) {
+                cast.complete(id, state, duration, start + duration);
+                debug!("complete {cast:?}");
+            } else {
+                let cast = Cast::from_end(start, id, state, duration);
+                debug!("complete without start {cast:?}");
+                data.add_cast(cast);
+            }
+        }
+    }
+
+    fn buff_cast(&mut self, event: &Event, src: &Agent, target: &Agent) {
+        let is_minion = self.is_own_minion(event);
+        if src.is_self == 0 && !is_minion {
+            return;
+        }
+        if let Some(info) = self
+            .data
+            .get_by_buff(event.skill_id)
+            .filter(|info| info.minion || !is_minion)
+        {
+            if let Some(buff) = &info.buff {
+                let id = info.id;
+                if let Some((time, fight)) = self.history.fight_and_time(event.time) {
+                    let data = &mut fight.data;
+                    let hit = buff
+                        .hits
+                        .then(|| Hit::new(time, id, target, HitOutcome::Normal, 0));
+                    match data.latest_cast_mut(id) {
+                        Some(cast) if time - cast.time <= buff.window => {
+                            // count each target reached only once
+                            if let Some(hit) = hit.filter(|hit| !cast.has_hit_on(hit.agent)) {
+                                debug!("buff hit {:?}, {hit:?}", cast.skill);
+                                cast.hit(hit);
+                            }
+                        }
+                        _ if buff.cast => {
+                            let mut cast = Cast::from_start(time, id, CastState::Instant);
+                            cast.hits.extend(hit);
+                            debug!("buff cast {cast:?}");
+                            data.add_cast(cast);
+                        }
+                        _ => {
+                            if let Some(hit) = hit {
+                                let cast = Cast::from_hit(time, id, hit);
+                                debug!("buff hit without start {:?}", cast.skill);
+                                data.add_cast(cast);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn apply_buff(
+        &mut self,
+        event: &Event,
+        buff: Buff,
+        stacking: Stacking,
+        src: &Agent,
+        dst: &Agent,
+    ) {
+        let is_own = src.is_self != 0 || self.is_own_minion(event);
+        let duration = event.value;
+        let instance = Self::buff_instance(event);
+
+        // simulate stacks for all applications to know the effective duration
+        let effective = if event.is_offcycle != 0 {
+            self.buffs
+                .extend(event.time, dst.id, buff, duration, instance);
+            duration
+        } else {
+            self.buffs.apply(
+                event.time,
+                dst.id,
+                buff,
+                stacking,
+                duration,
+                event.overstack_value,
+                is_own,
+                instance,
+            )
+        };
+
+        // only care about own buff applies to other where source and dest are different
+        if is_own && dst.is_self == 0 && dst.id != src.id {
+            if let Some((time, fight)) = self.history.fight_and_time(event.time) {
+                let apply = BuffApply::new(time, buff, duration, effective, dst.into());
+                fight.data.buffs.push(apply)
+            }
+        }
+    }
+
+    /// Returns the buff instance id of a buff apply.
+    fn buff_instance(event: &Event) -> u32 {
+        u32::from_le_bytes([event.pad61, event.pad62, event.pad63, event.pad64])
+    }
+
+    fn remove_buff_stacks(&mut self, event: &Event, buff: Buff, target: &Agent) {
+        match event.get_buffremove() {
+            BuffRemove::All => self.buffs.remove_all(event.time, target.id, buff),
+            BuffRemove::Single => {
+                self.buffs
+                    .remove_single(event.time, target.id, buff, event.value)
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_condi(&mut self, event: &Event, condi: Condition, target: &Agent) {
+        if let Some((time, fight)) = self.history.fight_and_time(event.time) {
+            let apply = Apply::new(time, condi, event.value, target.into());
+            fight.data.transfers.add_apply(apply);
+        }
+    }
+
+    fn remove_buff(&mut self, event: &Event, condi: Condition) {
+        if let Some((time, fight)) = self.history.fight_and_time(event.time) {
+            let remove = Remove::new(time, condi, event.value);
+            fight.data.transfers.add_remove(remove)
+        }
+    }
+
+    fn strike(
+        &mut self,
+        event: &Event,
+        skill_name: Option<&str>,
+        attacker: &Agent,
+        target: &Agent,
+    ) {
+        let id = event.skill_id;
+        self.skills.try_register(id, skill_name);
+        let is_minion = self.is_own_minion(event);
+        let is_own = attacker.is_self != 0 || is_minion;
+        match event.get_strike() {
+            Strike::Breakbar => {
+                let attacker = self
+                    .get_master(event)
+                    .map(|player| player.into())
+                    .unwrap_or(attacker.into());
+                self.breakbar_hit(event, attacker, is_own, target)
+            }
+            strike if is_own => {
+                // failed attempts like blocks or evades are kept as well
+                if let Some(outcome) = HitOutcome::from_strike(strike) {
+                    self.own_hit(event, EventSource::Area, outcome, is_minion, target)
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn own_hit(
+        &mut self,
+        event: &Event,
+        source: EventSource,
+        outcome: HitOutcome,
+        is_minion: bool,
+        target: &Agent,
+    ) {
+        // hits may be received from both area and local events
+        if !self.settings.local_hits || self.hit_filter.check(event, source) {
+            self.damage_hit(event, outcome, is_minion, target)
+        }
+    }
+
+    fn damage_hit(&mut self, event: &Event, outcome: HitOutcome, is_minion: bool, target: &Agent) {
+        let skill = event.skill_id;
+        if let Some(info) = self.data.get(skill) {
+            if info.minion || !is_minion {
+                let max = info.max_duration;
+                let id = info.id;
+                self.skills.try_duplicate(id, skill);
+                if let Some((time, fight)) = self.history.fight_and_time(event.time) {
+                    let data = &mut fight.data;
+                    let hit = Hit::new(time, skill, target, outcome, event.value);
+                    match data.latest_cast_mut(id) {
+                        Some(cast) if time - cast.time <= max => {
+                            debug!("hit {:?}, {hit:?}", cast.skill);
+                            cast.hit(hit);
+                        }
+                        _ => {
+                            let cast = Cast::from_hit(time, id, hit);
+                            debug!("hit without start {:?}, {:?}", cast.skill, cast.hits);
+                            data.add_cast(cast);
+                        }
+                    }
+                }
+            }
+        } else if self.settings.discovery && !is_minion && outcome.is_landed() {
+            self.discovery.hit(skill);
+        }
+    }
+
+    fn breakbar_hit(
+        &mut self,
+        event: &Event,
+        attacker: crate::combat::Agent,
+        is_own: bool,
+        target: &Agent,
+    ) {
+        let skill = event.skill_id;
+        let damage = event.value;
+        // TODO: minion indicator?
+        if let Some((time, fight)) = self.history.fight_and_time(event.time) {
+            debug!("breakbar {damage} {skill:?} from {attacker:?} to {target:?}");
+            let hit = BreakbarHit::new(time, skill, damage, attacker, is_own, target.into());
+            fight.data.breakbar.push(hit);
+        }
+    }
+}
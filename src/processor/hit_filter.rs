@@ -0,0 +1,145 @@
+use super::EventSource;
+use evtc::Event;
+use std::collections::VecDeque;
+
+/// Filter for hits received from multiple event sources.
+///
+/// Hits are counted per source.
+/// A hit is only considered a duplicate if the other source already reported a matching hit,
+/// so identical hits from the same source are kept.
+#[derive(Debug, Clone)]
+pub struct HitFilter {
+    /// Recently seen hits.
+    seen: VecDeque<SeenHit>,
+}
+
+impl HitFilter {
+    /// Time to retain seen hits.
+    pub const RETAIN_TIME: u64 = 5000;
+
+    /// Creates a new hit filter.
+    pub const fn new() -> Self {
+        Self {
+            seen: VecDeque::new(),
+        }
+    }
+
+    /// Checks whether the hit has not been reported by the other source yet and remembers it.
+    pub fn check(&mut self, event: &Event, source: EventSource) -> bool {
+        let key = HitKey::from(event);
+        self.seen
+            .retain(|other| other.key.time + Self::RETAIN_TIME >= key.time);
+        let seen = match self.seen.iter_mut().find(|other| other.key == key) {
+            Some(seen) => seen,
+            None => {
+                self.seen.push_back(SeenHit {
+                    key,
+                    area: 0,
+                    local: 0,
+                });
+                self.seen.back_mut().unwrap()
+            }
+        };
+        let (own, other) = match source {
+            EventSource::Area => (&mut seen.area, seen.local),
+            EventSource::Local => (&mut seen.local, seen.area),
+        };
+        *own += 1;
+
+        // hit is new if the other source did not report as many matching hits
+        *own > other
+    }
+}
+
+impl Default for HitFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Seen hit with the amount of times reported by each source.
+#[derive(Debug, Clone)]
+struct SeenHit {
+    key: HitKey,
+    area: usize,
+    local: usize,
+}
+
+/// Identifying information of a hit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HitKey {
+    time: u64,
+    skill: u32,
+    src: u64,
+    dst: u64,
+    value: i32,
+}
+
+impl From<&Event> for HitKey {
+    fn from(event: &Event) -> Self {
+        Self {
+            time: event.time,
+            skill: event.skill_id,
+            src: event.src_agent,
+            dst: event.dst_agent,
+            value: event.value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processor::tests::event;
+
+    fn hit(time: u64, value: i32) -> Event {
+        Event {
+            skill_id: 12345,
+            src_agent: 1,
+            dst_agent: 2,
+            value,
+            ..event(time)
+        }
+    }
+
+    #[test]
+    fn same_source_duplicates() {
+        let mut filter = HitFilter::new();
+        assert!(filter.check(&hit(1000, 100), EventSource::Area));
+        assert!(filter.check(&hit(1000, 100), EventSource::Area));
+
+        let mut filter = HitFilter::new();
+        assert!(filter.check(&hit(1000, 100), EventSource::Local));
+        assert!(filter.check(&hit(1000, 100), EventSource::Local));
+    }
+
+    #[test]
+    fn other_source_matches() {
+        let mut filter = HitFilter::new();
+        assert!(filter.check(&hit(1000, 100), EventSource::Area));
+        assert!(filter.check(&hit(1000, 100), EventSource::Area));
+        assert!(!filter.check(&hit(1000, 100), EventSource::Local));
+        assert!(!filter.check(&hit(1000, 100), EventSource::Local));
+        assert!(filter.check(&hit(1000, 100), EventSource::Local));
+        assert!(!filter.check(&hit(1000, 100), EventSource::Area));
+    }
+
+    #[test]
+    fn different_hits() {
+        let mut filter = HitFilter::new();
+        assert!(filter.check(&hit(1000, 100), EventSource::Local));
+        assert!(filter.check(&hit(1000, 200), EventSource::Area));
+        assert!(filter.check(&hit(1001, 100), EventSource::Area));
+    }
+
+    #[test]
+    fn retain_time() {
+        let mut filter = HitFilter::new();
+        assert!(filter.check(&hit(1000, 100), EventSource::Area));
+        filter.check(
+            &hit(1000 + HitFilter::RETAIN_TIME + 1, 0),
+            EventSource::Area,
+        );
+        assert!(filter.check(&hit(1000, 100), EventSource::Local));
+    }
+}
@@ -0,0 +1,279 @@
+mod event;
+mod hit_filter;
+mod queue;
+mod settings;
+
+pub use self::{hit_filter::*, queue::*, settings::*};
+
+use crate::{
+    combat::{
+        buff::BuffTracker, discovery::Discovery, player::Player, skill::SkillMap, CombatData,
+    },
+    data::{BuffData, Build, SkillData},
+    history::History,
+};
+
+/// Combat event processor.
+///
+/// Keeps the state required to evaluate combat events independent of the UI.
+#[derive(Debug)]
+pub struct Processor {
+    pub settings: ProcessorSettings,
+
+    pub skills: SkillMap,
+    pub data: SkillData,
+    pub buff_data: BuffData,
+
+    pub self_instance_id: Option<u16>,
+    pub players: Vec<Player>,
+    pub history: History<CombatData>,
+
+    pub buffs: BuffTracker,
+    pub discovery: Discovery,
+    hit_filter: HitFilter,
+}
+
+impl Processor {
+    /// Creates a new processor.
+    pub fn new(skills: SkillMap, data: SkillData, history: History<CombatData>) -> Self {
+        let mut processor = Self {
+            settings: ProcessorSettings::new(),
+            skills,
+            data,
+            buff_data: BuffData::with_defaults(),
+            self_instance_id: None,
+            players: Vec::new(),
+            history,
+            buffs: BuffTracker::new(),
+            discovery: Discovery::new(),
+            hit_filter: HitFilter::new(),
+        };
+        processor.update_skill_names();
+        processor
+    }
+
+    /// Replaces the skill data.
+    ///
+    /// Casts in the history are re-evaluated against the new data.
+    pub fn set_data(&mut self, mut data: SkillData) {
+        data.set_build(self.data.build());
+        self.data = data;
+        self.update_skill_names();
+        for fight in self.history.all_fights_mut() {
+            fight.data.reevaluate(&self.data);
+        }
+    }
+
+    /// Replaces the buff data.
+    ///
+    /// Simulated stacks are reset, buffs already logged keep their id.
+    pub fn set_buff_data(&mut self, data: BuffData) {
+        self.buff_data = data;
+        self.buffs = BuffTracker::new();
+    }
+
+    /// Restricts the active skill data to the given build.
+    pub fn set_build(&mut self, build: Option<Build>) {
+        if build != self.data.build() {
+            self.data.set_build(build);
+            self.update_skill_names();
+        }
+    }
+
+    /// Updates the skill name overrides from the skill data.
+    pub fn update_skill_names(&mut self) {
+        self.skills.set_overrides(self.data.names());
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{combat::cast::CastState, data::SkillDef, history::Fight};
+    use evtc::{Activation, Agent, Event, Strike};
+    use std::ptr;
+
+    const SKILL: u32 = 5491;
+    const HIT_SKILL: u32 = 5492;
+
+    pub fn event(time: u64) -> Event {
+        Event {
+            time,
+            src_agent: 0,
+            dst_agent: 0,
+            value: 0,
+            buff_dmg: 0,
+            overstack_value: 0,
+            skill_id: 0,
+            src_instance_id: 0,
+            dst_instance_id: 0,
+            src_master_instance_id: 0,
+            dst_master_instance_id: 0,
+            affinity: 0,
+            buff: 0,
+            result: 0,
+            is_activation: 0,
+            is_buffremove: 0,
+            is_ninety: 0,
+            is_fifty: 0,
+            is_moving: 0,
+            is_statechange: 0,
+            is_flanking: 0,
+            is_shields: 0,
+            is_offcycle: 0,
+            pad61: 0,
+            pad62: 0,
+            pad63: 0,
+            pad64: 0,
+        }
+    }
+
+    fn agent(id: usize, is_self: bool) -> Agent {
+        Agent {
+            name: ptr::null(),
+            id,
+            prof: 1,
+            elite: 0,
+            is_self: is_self.into(),
+            team: 0,
+        }
+    }
+
+    fn activation(time: u64, skill: u32, activation: Activation, duration: i32) -> Event {
+        Event {
+            skill_id: skill,
+            is_activation: activation.into(),
+            value: duration,
+            ..event(time)
+        }
+    }
+
+    fn strike(time: u64, skill: u32, strike: Strike, damage: i32) -> Event {
+        Event {
+            skill_id: skill,
+            result: strike.into(),
+            value: damage,
+            src_agent: 1,
+            dst_agent: 2,
+            ..event(time)
+        }
+    }
+
+    fn processor() -> Processor {
+        let data = SkillData::new([SkillDef {
+            hit_ids: vec![HIT_SKILL],
+            hits: Some(2),
+            ..SkillDef::new(SKILL)
+        }]);
+        let mut processor = Processor::new(SkillMap::new(), data, History::new(10, 0, false));
+        processor
+            .history
+            .add_fight(Fight::new(1000, CombatData::new()));
+        processor
+    }
+
+    fn feed(processor: &mut Processor, events: &[Event]) {
+        let (player, target) = (agent(1, true), agent(2, false));
+        for event in events {
+            processor.area_event(Some(event), Some(&player), Some(&target), None);
+        }
+    }
+
+    #[test]
+    fn cast_with_hits() {
+        let mut processor = processor();
+        feed(
+            &mut processor,
+            &[
+                activation(1100, SKILL, Activation::Start, 0),
+                strike(1300, HIT_SKILL, Strike::Normal, 500),
+                strike(1400, HIT_SKILL, Strike::Evade, 0),
+                strike(1500, SKILL, Strike::Crit, 1000),
+                activation(1600, SKILL, Activation::Reset, 500),
+            ],
+        );
+
+        let casts = &processor.history.latest_fight().unwrap().data.casts;
+        assert_eq!(casts.len(), 1);
+        let cast = &casts[0];
+        assert_eq!(cast.skill, SKILL);
+        assert_eq!(cast.time, 100);
+        assert_eq!(cast.state, CastState::Fire);
+        assert_eq!(cast.duration, 500);
+        assert_eq!(cast.hits.len(), 3);
+        assert_eq!(cast.hit_count(), 2);
+        assert_eq!(cast.damage(), 1500);
+    }
+
+    #[test]
+    fn hits_without_start() {
+        let mut processor = processor();
+        feed(
+            &mut processor,
+            &[
+                strike(1100, HIT_SKILL, Strike::Normal, 500),
+                activation(1400, SKILL, Activation::CancelFire, 400),
+                activation(2000, SKILL, Activation::Start, 0),
+                activation(2200, SKILL, Activation::CancelCancel, 200),
+            ],
+        );
+
+        let casts = &processor.history.latest_fight().unwrap().data.casts;
+        assert_eq!(casts.len(), 2);
+
+        // cast end completes the cast created from the hit
+        assert_eq!(casts[0].time, 0);
+        assert_eq!(casts[0].state, CastState::Cancel);
+        assert_eq!(casts[0].hit_count(), 1);
+
+        assert_eq!(casts[1].time, 1000);
+        assert_eq!(casts[1].state, CastState::Interrupt);
+        assert!(casts[1].hits.is_empty());
+    }
+
+    #[test]
+    fn player_tracking() {
+        let mut processor = processor();
+        let track = |processor: &mut Processor, id, prof| {
+            let src = Agent {
+                prof,
+                ..agent(id, false)
+            };
+            let dst = Agent {
+                id: 100 + id,
+                ..agent(id, false)
+            };
+            processor.area_event(None, Some(&src), Some(&dst), None);
+        };
+
+        track(&mut processor, 1, 1);
+        track(&mut processor, 2, 1);
+        track(&mut processor, 1, 1);
+        assert_eq!(processor.players.len(), 2);
+
+        // removal has no profession
+        track(&mut processor, 1, 0);
+        assert_eq!(processor.players.len(), 1);
+        assert_eq!(processor.players[0].id, 2);
+        assert_eq!(processor.players[0].instance_id, 102);
+    }
+
+    #[test]
+    fn other_skills_ignored() {
+        let mut processor = processor();
+        feed(
+            &mut processor,
+            &[
+                activation(1100, 1, Activation::Start, 0),
+                strike(1200, 1, Strike::Normal, 500),
+            ],
+        );
+        assert!(processor
+            .history
+            .latest_fight()
+            .unwrap()
+            .data
+            .casts
+            .is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+use super::Processor;
+use evtc::{Agent, Event};
+use log::warn;
+use std::{
+    ffi::{CStr, CString},
+    ptr,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Maximum amount of events waiting to be drained.
+///
+/// Events pushed while the queue is full are dropped.
+pub const QUEUE_CAPACITY: usize = 100_000;
+
+/// Time events are held back to reorder events delivered late.
+pub const REORDER_DELAY: Duration = Duration::from_millis(500);
+
+/// Queue of combat events waiting to be processed.
+///
+/// Pushing never blocks, only draining acquires a lock.
+#[derive(Debug)]
+pub struct EventQueue {
+    sender: SyncSender<QueuedEvent>,
+    state: Mutex<QueueState>,
+    dropped: AtomicUsize,
+
+    /// Latest event time seen, used to order events without time.
+    latest: AtomicU64,
+}
+
+/// Receiving side of the queue.
+#[derive(Debug)]
+struct QueueState {
+    receiver: Receiver<QueuedEvent>,
+
+    /// Events held back for reordering with the time they were received.
+    held: Vec<(Instant, QueuedEvent)>,
+}
+
+impl EventQueue {
+    /// Creates a new event queue.
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+        Self {
+            sender,
+            state: Mutex::new(QueueState {
+                receiver,
+                held: Vec::new(),
+            }),
+            dropped: AtomicUsize::new(0),
+            latest: AtomicU64::new(0),
+        }
+    }
+
+    /// Pushes an event onto the queue.
+    ///
+    /// Events without time are ordered after the latest event pushed before them.
+    /// The event is dropped if the queue is full.
+    pub fn push(&self, mut event: QueuedEvent) {
+        match &event.event {
+            Some(inner) => {
+                self.latest.fetch_max(inner.time, Ordering::Relaxed);
+            }
+            None => event.time = self.latest.load(Ordering::Relaxed),
+        }
+
+        // receiver lives as long as the queue
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(event) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Takes all queued events, including events held back for reordering.
+    pub fn drain_all(&self) -> Vec<QueuedEvent> {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let QueueState { receiver, held } = &mut *state;
+        let mut events: Vec<_> = held.drain(..).map(|(_, event)| event).collect();
+        events.extend(receiver.try_iter());
+        events
+    }
+
+    /// Takes all queued events ready for processing.
+    ///
+    /// Events are held back until they were received [`REORDER_DELAY`] ago
+    /// or an event [`REORDER_DELAY`] later in combat time was received.
+    /// This way events delivered late are still processed in order.
+    pub fn drain(&self) -> Vec<QueuedEvent> {
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            warn!("Dropped {dropped} combat events, event queue was full");
+        }
+
+        // a panic while holding the lock leaves the state intact
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let QueueState { receiver, held } = &mut *state;
+
+        let now = Instant::now();
+        held.extend(receiver.try_iter().map(|event| (now, event)));
+
+        let delay = REORDER_DELAY.as_millis() as u64;
+        let newest = held.iter().map(|(_, event)| event.time()).max();
+        let (ready, keep) = held.drain(..).partition::<Vec<_>, _>(|(received, event)| {
+            now.duration_since(*received) >= REORDER_DELAY
+                || matches!(newest, Some(newest) if event.time().saturating_add(delay) <= newest)
+        });
+        *held = keep;
+        ready.into_iter().map(|(_, event)| event).collect()
+    }
+}
+
+impl Default for EventQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Source of a combat event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventSource {
+    /// Area stats.
+    Area,
+
+    /// Local stats.
+    Local,
+}
+
+/// Owned copy of a combat event for deferred processing.
+#[derive(Debug, Clone)]
+pub struct QueuedEvent {
+    source: EventSource,
+    time: u64,
+    event: Option<Event>,
+    src: Option<QueuedAgent>,
+    dst: Option<QueuedAgent>,
+    skill_name: Option<String>,
+}
+
+impl QueuedEvent {
+    /// Creates a new queued event from the callback arguments.
+    pub fn new(
+        source: EventSource,
+        event: Option<&Event>,
+        src: Option<&Agent>,
+        dst: Option<&Agent>,
+        skill_name: Option<&str>,
+    ) -> Self {
+        Self {
+            source,
+            time: event.map_or(0, |event| event.time),
+            event: event.cloned(),
+            src: src.map(QueuedAgent::new),
+            dst: dst.map(QueuedAgent::new),
+            skill_name: skill_name.map(Into::into),
+        }
+    }
+
+    /// Returns the time used to order the event.
+    ///
+    /// Events without time (tracking changes) use the time of the latest event pushed before them.
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+}
+
+/// Owned copy of an agent.
+#[derive(Debug, Clone)]
+struct QueuedAgent {
+    name: Option<CString>,
+    id: usize,
+    prof: u32,
+    elite: u32,
+    is_self: u32,
+    team: u16,
+}
+
+impl QueuedAgent {
+    /// Creates an owned copy of the agent.
+    fn new(agent: &Agent) -> Self {
+        Self {
+            name: agent.name().and_then(|name| CString::new(name).ok()),
+            id: agent.id,
+            prof: agent.prof,
+            elite: agent.elite,
+            is_self: agent.is_self,
+            team: agent.team,
+        }
+    }
+
+    /// Returns an agent borrowing the owned name.
+    fn as_agent(&self) -> Agent {
+        Agent {
+            name: self.name.as_deref().map_or(ptr::null(), CStr::as_ptr),
+            id: self.id,
+            prof: self.prof,
+            elite: self.elite,
+            is_self: self.is_self,
+            team: self.team,
+        }
+    }
+}
+
+impl Processor {
+    /// Processes a batch of queued events.
+    ///
+    /// Events are processed in order of their time, keeping the order of events with the same time.
+    /// Events delivered late are reordered by the [`EventQueue`] before.
+    pub fn process_batch(&mut self, mut events: Vec<QueuedEvent>) {
+        events.sort_by_key(QueuedEvent::time);
+        for queued in &events {
+            let src = queued.src.as_ref().map(QueuedAgent::as_agent);
+            let dst = queued.dst.as_ref().map(QueuedAgent::as_agent);
+            let event = queued.event.as_ref();
+            let skill_name = queued.skill_name.as_deref();
+            match queued.source {
+                EventSource::Area => self.area_event(event, src.as_ref(), dst.as_ref(), skill_name),
+                EventSource::Local => {
+                    self.local_event(event, src.as_ref(), dst.as_ref(), skill_name)
+                }
+            }
+        }
+        if let Some(event) = events.iter().rev().find_map(|queued| queued.event.as_ref()) {
+            self.flush_buffs(event.time);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processor::tests::event;
+
+    fn queued(time: Option<u64>) -> QueuedEvent {
+        let event = time.map(event);
+        QueuedEvent::new(EventSource::Area, event.as_ref(), None, None, None)
+    }
+
+    #[test]
+    fn untimed_order() {
+        let queue = EventQueue::new();
+        queue.push(queued(Some(1000)));
+        queue.push(queued(None));
+        queue.push(queued(Some(900)));
+        queue.push(queued(Some(1200)));
+
+        // untimed events are held back with the timed events around them
+        assert!(queue.drain().is_empty());
+
+        let mut events = queue.drain_all();
+        events.sort_by_key(QueuedEvent::time);
+        let order: Vec<_> = events
+            .iter()
+            .map(|queued| queued.event.as_ref().map(|event| event.time))
+            .collect();
+        assert_eq!(order, [Some(900), Some(1000), None, Some(1200)]);
+    }
+
+    #[test]
+    fn release_later() {
+        let queue = EventQueue::new();
+        queue.push(queued(Some(1000)));
+        queue.push(queued(None));
+        queue.push(queued(Some(1600)));
+
+        let ready = queue.drain();
+        assert_eq!(ready.len(), 2);
+        assert_eq!(ready[0].time(), 1000);
+        assert!(ready[1].event.is_none());
+        assert_eq!(queue.drain_all().len(), 1);
+    }
+}
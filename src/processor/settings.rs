@@ -0,0 +1,51 @@
+use crate::data::DataSource;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "plugin")]
+use super::Processor;
+#[cfg(feature = "plugin")]
+use arc_util::settings::HasSettings;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessorSettings {
+    /// Whether to additionally use local combat events for hits.
+    pub local_hits: bool,
+
+    /// Source of skill definitions.
+    pub data_source: DataSource,
+
+    /// Whether to record own skills without definition.
+    pub discovery: bool,
+}
+
+impl ProcessorSettings {
+    pub const fn new() -> Self {
+        Self {
+            local_hits: false,
+            data_source: DataSource::Merged,
+            discovery: false,
+        }
+    }
+}
+
+impl Default for ProcessorSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "plugin")]
+impl HasSettings for Processor {
+    type Settings = ProcessorSettings;
+
+    const SETTINGS_ID: &'static str = "processor";
+
+    fn current_settings(&self) -> Self::Settings {
+        self.settings.clone()
+    }
+
+    fn load_settings(&mut self, loaded: Self::Settings) {
+        self.settings = loaded;
+    }
+}
@@ -0,0 +1,370 @@
+use evtc::Event;
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Seek},
+    path::Path,
+};
+use zip::{result::ZipError, ZipArchive};
+
+/// Supported EVTC revision.
+pub const REVISION: u8 = 1;
+
+/// Magic bytes at the start of an EVTC file.
+const EVTC_MAGIC: [u8; 4] = *b"EVTC";
+
+/// Magic bytes at the start of a zip archive.
+const ZIP_MAGIC: [u8; 4] = *b"PK\x03\x04";
+
+/// Length of fixed size name fields.
+const NAME_LEN: usize = 64;
+
+/// Parsed EVTC log file.
+#[derive(Debug, Clone)]
+pub struct EvtcFile {
+    /// ArcDPS build date.
+    pub build: String,
+
+    /// Species id of the log target.
+    pub boss_id: u16,
+
+    /// Agents present in the log.
+    pub agents: Vec<FileAgent>,
+
+    /// Skill ids and names present in the log.
+    pub skills: Vec<(u32, String)>,
+
+    /// Combat events in the log.
+    pub events: Vec<Event>,
+}
+
+impl EvtcFile {
+    /// Reads a log from the given file path.
+    ///
+    /// Compressed `.zevtc` logs are detected automatically.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let magic: [u8; 4] = read_array(&mut reader)?;
+        reader.rewind()?;
+
+        if magic == ZIP_MAGIC {
+            let mut archive = ZipArchive::new(reader)?;
+            let entry = archive.by_index(0)?;
+            Self::read(BufReader::new(entry))
+        } else {
+            Self::read(reader)
+        }
+    }
+
+    /// Reads a log from an uncompressed EVTC stream.
+    pub fn read(mut reader: impl Read) -> Result<Self, ReplayError> {
+        let magic: [u8; 4] = read_array(&mut reader)?;
+        if magic != EVTC_MAGIC {
+            return Err(ReplayError::Invalid);
+        }
+        let build: [u8; 8] = read_array(&mut reader)?;
+        let revision = read_u8(&mut reader)?;
+        if revision != REVISION {
+            return Err(ReplayError::UnsupportedRevision(revision));
+        }
+        let boss_id = read_u16(&mut reader)?;
+        let _unused = read_u8(&mut reader)?;
+
+        let agent_count = read_u32(&mut reader)?;
+        let agents = (0..agent_count)
+            .map(|_| FileAgent::read(&mut reader))
+            .collect::<io::Result<_>>()?;
+
+        let skill_count = read_u32(&mut reader)?;
+        let skills = (0..skill_count)
+            .map(|_| {
+                let id = read_u32(&mut reader)?;
+                let name = read_array::<NAME_LEN>(&mut reader)?;
+                let name = parse_name(&name).next().unwrap_or_default().to_owned();
+                Ok((id, name))
+            })
+            .collect::<io::Result<_>>()?;
+
+        let mut events = Vec::new();
+        loop {
+            match read_event(&mut reader) {
+                Ok(event) => events.push(event),
+                // a partially written trailing event is ignored
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Ok(Self {
+            build: String::from_utf8_lossy(&build).into_owned(),
+            boss_id,
+            agents,
+            skills,
+            events,
+        })
+    }
+}
+
+/// Agent entry in an EVTC log file.
+#[derive(Debug, Clone)]
+pub struct FileAgent {
+    /// Agent address used as id in events.
+    pub address: u64,
+
+    /// Profession for players, species id for NPCs & gadgets.
+    pub prof: u32,
+
+    /// Elite specialization for players, `u32::MAX` for NPCs & gadgets.
+    pub elite: u32,
+
+    /// Agent name, character name for players.
+    pub name: String,
+
+    /// Account name for players.
+    pub account: Option<String>,
+}
+
+impl FileAgent {
+    /// Reads an agent entry.
+    fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let address = read_u64(reader)?;
+        let prof = read_u32(reader)?;
+        let elite = read_u32(reader)?;
+        let _stats: [u8; 12] = read_array(reader)?;
+        let name: [u8; NAME_LEN] = read_array(reader)?;
+        let _padding: [u8; 4] = read_array(reader)?;
+
+        let mut parts = parse_name(&name);
+        Ok(Self {
+            address,
+            prof,
+            elite,
+            name: parts.next().unwrap_or_default().into(),
+            account: parts
+                .next()
+                .map(|account| account.trim_start_matches(':'))
+                .filter(|account| !account.is_empty())
+                .map(Into::into),
+        })
+    }
+
+    /// Checks whether the agent is a player.
+    pub fn is_player(&self) -> bool {
+        self.elite != u32::MAX
+    }
+
+    /// Returns the species id if the agent is a NPC.
+    pub fn species(&self) -> Option<u16> {
+        if !self.is_player() && self.prof & 0xffff0000 != 0xffff0000 {
+            Some(self.prof as u16)
+        } else {
+            None
+        }
+    }
+}
+
+/// Error while reading a log file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReplayError {
+    NotFound,
+    FailedToRead,
+    Invalid,
+    UnsupportedRevision(u8),
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => Self::NotFound,
+            io::ErrorKind::UnexpectedEof => Self::Invalid,
+            _ => Self::FailedToRead,
+        }
+    }
+}
+
+impl From<ZipError> for ReplayError {
+    fn from(err: ZipError) -> Self {
+        match err {
+            ZipError::Io(err) => err.into(),
+            _ => Self::Invalid,
+        }
+    }
+}
+
+/// Splits a fixed size name field into its null-terminated parts.
+fn parse_name(bytes: &[u8]) -> impl Iterator<Item = &str> {
+    bytes
+        .split(|byte| *byte == 0)
+        .map(|part| std::str::from_utf8(part).unwrap_or_default())
+        .take_while(|part| !part.is_empty())
+}
+
+/// Reads a single revision 1 combat event.
+fn read_event(reader: &mut impl Read) -> io::Result<Event> {
+    Ok(Event {
+        time: read_u64(reader)?,
+        src_agent: read_u64(reader)?,
+        dst_agent: read_u64(reader)?,
+        value: read_u32(reader)? as i32,
+        buff_dmg: read_u32(reader)? as i32,
+        overstack_value: read_u32(reader)?,
+        skill_id: read_u32(reader)?,
+        src_instance_id: read_u16(reader)?,
+        dst_instance_id: read_u16(reader)?,
+        src_master_instance_id: read_u16(reader)?,
+        dst_master_instance_id: read_u16(reader)?,
+        affinity: read_u8(reader)?,
+        buff: read_u8(reader)?,
+        result: read_u8(reader)?,
+        is_activation: read_u8(reader)?,
+        is_buffremove: read_u8(reader)?,
+        is_ninety: read_u8(reader)?,
+        is_fifty: read_u8(reader)?,
+        is_moving: read_u8(reader)?,
+        is_statechange: read_u8(reader)?,
+        is_flanking: read_u8(reader)?,
+        is_shields: read_u8(reader)?,
+        is_offcycle: read_u8(reader)?,
+        pad61: read_u8(reader)?,
+        pad62: read_u8(reader)?,
+        pad63: read_u8(reader)?,
+        pad64: read_u8(reader)?,
+    })
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    read_array(reader).map(u8::from_le_bytes)
+}
+
+fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+    read_array(reader).map(u16::from_le_bytes)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    read_array(reader).map(u32::from_le_bytes)
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    read_array(reader).map(u64::from_le_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name_field(parts: &[&str]) -> [u8; NAME_LEN] {
+        let mut field = [0; NAME_LEN];
+        let mut offset = 0;
+        for part in parts {
+            field[offset..offset + part.len()].copy_from_slice(part.as_bytes());
+            offset += part.len() + 1;
+        }
+        field
+    }
+
+    fn log(revision: u8, trailing: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(EVTC_MAGIC);
+        bytes.extend(b"20240101");
+        bytes.push(revision);
+        bytes.extend(17154u16.to_le_bytes());
+        bytes.push(0);
+
+        // agents
+        bytes.extend(2u32.to_le_bytes());
+        bytes.extend(0x1000u64.to_le_bytes());
+        bytes.extend(7u32.to_le_bytes());
+        bytes.extend(40u32.to_le_bytes());
+        bytes.extend([0; 12]);
+        bytes.extend(name_field(&["Player", ":Account.1234", "1"]));
+        bytes.extend([0; 4]);
+        bytes.extend(0x2000u64.to_le_bytes());
+        bytes.extend((0xffff0000u32 | 17154).to_le_bytes());
+        bytes.extend(u32::MAX.to_le_bytes());
+        bytes.extend([0; 12]);
+        bytes.extend(name_field(&["Boss"]));
+        bytes.extend([0; 4]);
+
+        // skills
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend(5492u32.to_le_bytes());
+        bytes.extend(name_field(&["Fire Attunement"]));
+
+        // events
+        let mut event = Vec::new();
+        event.extend(1234u64.to_le_bytes());
+        event.extend(0x1000u64.to_le_bytes());
+        event.extend(0x2000u64.to_le_bytes());
+        event.extend((-100i32).to_le_bytes());
+        event.extend(0u32.to_le_bytes());
+        event.extend(5u32.to_le_bytes());
+        event.extend(5492u32.to_le_bytes());
+        event.extend(1u16.to_le_bytes());
+        event.extend(2u16.to_le_bytes());
+        event.extend(0u16.to_le_bytes());
+        event.extend(0u16.to_le_bytes());
+        event.extend([0; 16]);
+        assert_eq!(event.len(), 64);
+        bytes.extend(&event);
+        bytes.extend(trailing);
+        bytes
+    }
+
+    #[test]
+    fn read_rev1() {
+        let log = EvtcFile::read(log(1, &[]).as_slice()).unwrap();
+        assert_eq!(log.build, "20240101");
+        assert_eq!(log.boss_id, 17154);
+
+        assert_eq!(log.agents.len(), 2);
+        let player = &log.agents[0];
+        assert_eq!(player.address, 0x1000);
+        assert_eq!(player.name, "Player");
+        assert_eq!(player.account.as_deref(), Some("Account.1234"));
+        assert!(player.is_player());
+        assert_eq!(player.species(), None);
+        let boss = &log.agents[1];
+        assert!(!boss.is_player());
+        assert_eq!(boss.account, None);
+
+        assert_eq!(log.skills, vec![(5492, "Fire Attunement".into())]);
+
+        assert_eq!(log.events.len(), 1);
+        let event = &log.events[0];
+        assert_eq!(event.time, 1234);
+        assert_eq!(event.src_agent, 0x1000);
+        assert_eq!(event.dst_agent, 0x2000);
+        assert_eq!(event.value, -100);
+        assert_eq!(event.overstack_value, 5);
+        assert_eq!(event.skill_id, 5492);
+        assert_eq!(event.src_instance_id, 1);
+        assert_eq!(event.dst_instance_id, 2);
+    }
+
+    #[test]
+    fn partial_event() {
+        let log = EvtcFile::read(log(1, &[0; 20]).as_slice()).unwrap();
+        assert_eq!(log.events.len(), 1);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            EvtcFile::read(log(0, &[]).as_slice()).unwrap_err(),
+            ReplayError::UnsupportedRevision(0)
+        );
+        assert_eq!(
+            EvtcFile::read(&b"PK\x03\x04"[..]).unwrap_err(),
+            ReplayError::Invalid
+        );
+        assert_eq!(
+            EvtcFile::read(&log(1, &[])[..20]).unwrap_err(),
+            ReplayError::Invalid
+        );
+    }
+}
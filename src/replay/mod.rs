@@ -0,0 +1,177 @@
+mod file;
+
+pub use self::file::*;
+
+use crate::processor::Processor;
+use evtc::{Agent, EventCategory, StateChange};
+use log::debug;
+use std::{
+    collections::HashMap,
+    ffi::{c_char, CString},
+};
+
+impl Processor {
+    /// Replays the events of a recorded log.
+    ///
+    /// Events are passed through the same handlers as live area events.
+    pub fn replay(&mut self, log: &EvtcFile) {
+        debug!(
+            "replay log from build {} for {} with {} events",
+            log.build,
+            log.boss_id,
+            log.events.len()
+        );
+        let agents = ReplayAgents::new(log);
+        let skills: HashMap<u32, &str> = log
+            .skills
+            .iter()
+            .map(|(id, name)| (*id, name.as_str()))
+            .collect();
+
+        for (src, dst) in &agents.tracking {
+            self.area_event(None, Some(src), Some(dst), None);
+        }
+
+        for event in &log.events {
+            let (src, dst) = match event.categorize() {
+                // state changes may carry species ids instead of agent addresses
+                EventCategory::StateChange => (
+                    agents.get(event.src_agent).or_else(|| agents.pov()),
+                    agents
+                        .get(event.dst_agent)
+                        .or_else(|| agents.species(event.src_agent)),
+                ),
+                _ => (agents.get(event.src_agent), agents.get(event.dst_agent)),
+            };
+            let skill_name = skills.get(&event.skill_id).copied();
+            self.area_event(Some(event), src, dst, skill_name);
+        }
+
+        // logs may be missing the final log end
+        if let Some(last) = log.events.last() {
+            self.flush_buffs(last.time);
+            self.discovery.complete();
+            self.history.end_latest_fight(last.time);
+        }
+    }
+}
+
+/// Realtime agents reconstructed from a log.
+#[derive(Debug)]
+struct ReplayAgents {
+    /// Agents by address.
+    agents: HashMap<u64, Agent>,
+
+    /// Tracking additions for players.
+    tracking: Vec<(Agent, Agent)>,
+
+    /// Agent addresses by species id.
+    species: HashMap<u16, u64>,
+
+    /// Address of the recording player.
+    pov: Option<u64>,
+
+    /// Owned names referenced by the agents.
+    _names: Vec<CString>,
+}
+
+impl ReplayAgents {
+    /// Creates the realtime agents for a log.
+    fn new(log: &EvtcFile) -> Self {
+        let pov = log
+            .events
+            .iter()
+            .find(|event| event.get_statechange() == StateChange::PointOfView)
+            .map(|event| event.src_agent);
+
+        let mut instance_ids = HashMap::new();
+        for event in &log.events {
+            if !matches!(event.categorize(), EventCategory::StateChange) {
+                instance_ids
+                    .entry(event.src_agent)
+                    .or_insert(event.src_instance_id);
+            }
+        }
+
+        let mut names = Vec::new();
+        let mut agents = HashMap::with_capacity(log.agents.len());
+        let mut tracking = Vec::new();
+        let mut species = HashMap::new();
+        for agent in &log.agents {
+            if let Some(id) = agent.species() {
+                species.entry(id).or_insert(agent.address);
+            }
+
+            let is_self = (pov == Some(agent.address)) as u32;
+            let name = Self::store_name(&mut names, &agent.name);
+            agents.insert(
+                agent.address,
+                Agent {
+                    name,
+                    id: agent.address as usize,
+                    prof: agent.prof,
+                    elite: agent.elite,
+                    is_self,
+                    team: 0,
+                },
+            );
+
+            if agent.is_player() {
+                if let Some(instance_id) = instance_ids.get(&agent.address) {
+                    let account = agent.account.as_deref().unwrap_or_default();
+                    let src = Agent {
+                        name,
+                        id: agent.address as usize,
+                        prof: agent.prof,
+                        elite: 0,
+                        is_self: 0,
+                        team: 0,
+                    };
+                    let dst = Agent {
+                        name: Self::store_name(&mut names, account),
+                        id: *instance_id as usize,
+                        prof: agent.prof,
+                        elite: agent.elite,
+                        is_self,
+                        team: 0,
+                    };
+                    tracking.push((src, dst));
+                }
+            }
+        }
+
+        Self {
+            agents,
+            tracking,
+            species,
+            pov,
+            _names: names,
+        }
+    }
+
+    /// Stores a name, returning a pointer valid for the lifetime of the agents.
+    fn store_name(names: &mut Vec<CString>, name: &str) -> *const c_char {
+        let name = CString::new(name).unwrap_or_default();
+        let ptr = name.as_ptr();
+        names.push(name);
+        ptr
+    }
+
+    /// Returns the agent with the given address.
+    fn get(&self, address: u64) -> Option<&Agent> {
+        self.agents.get(&address)
+    }
+
+    /// Returns the agent of the recording player.
+    fn pov(&self) -> Option<&Agent> {
+        self.pov.and_then(|address| self.get(address))
+    }
+
+    /// Returns the first NPC agent with the given species id.
+    fn species(&self, species: u64) -> Option<&Agent> {
+        u16::try_from(species)
+            .ok()
+            .and_then(|species| self.species.get(&species))
+            .and_then(|address| self.get(*address))
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{
     combat::{agent::AgentFilter, CombatData},
+    data::BuffData,
     history::History,
     ui::{format_time, scroll::AutoScroll},
 };
 use arc_util::{
-    colors::{GREY, YELLOW},
+    colors::{GREEN, GREY, RED, YELLOW},
     settings::HasSettings,
     ui::{
         render::{ch_width, enum_combo_array},
@@ -22,6 +23,7 @@ use serde::{Deserialize, Serialize};
 pub struct BuffLog {
     display_time: bool,
     display_duration: bool,
+    display_effective: bool,
     target_filter: AgentFilter,
 
     #[serde(skip)]
@@ -33,6 +35,7 @@ impl BuffLog {
         Self {
             display_time: true,
             display_duration: true,
+            display_effective: true,
             target_filter: AgentFilter::All,
             scroll: AutoScroll::new(),
         }
@@ -41,25 +44,36 @@ impl BuffLog {
     pub fn render_display(&mut self, ui: &Ui) {
         ui.checkbox("Display time", &mut self.display_time);
         ui.checkbox("Display duration", &mut self.display_duration);
+        ui.checkbox("Display effective duration", &mut self.display_effective);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Duration actually added, excluding overstack");
+        }
 
         ui.set_next_item_width(ch_width(ui, 16));
         enum_combo_array(ui, "Targets", &mut self.target_filter);
     }
+
+    fn format_duration(duration: i32) -> String {
+        format!("{}.{:03}s", duration / 1000, duration.abs() % 1000)
+    }
 }
 
 #[derive(Debug)]
 pub struct BuffLogProps<'a> {
+    pub buff_data: &'a BuffData,
     pub history: &'a mut History<CombatData>,
 }
 
 impl Component<BuffLogProps<'_>> for BuffLog {
     fn render(&mut self, ui: &Ui, props: BuffLogProps) {
-        let BuffLogProps { history } = props;
+        let BuffLogProps { buff_data, history } = props;
 
         if let Some(fight) = history.viewed_fight() {
             let colors = exports::colors();
             let grey = colors.core(CoreColor::MediumGrey).unwrap_or(GREY);
             let yellow = colors.core(CoreColor::LightYellow).unwrap_or(YELLOW);
+            let red = colors.core(CoreColor::LightRed).unwrap_or(RED);
+            let green = colors.core(CoreColor::LightGreen).unwrap_or(GREEN);
 
             for apply in &fight.data.buffs {
                 if self.target_filter.matches(&apply.target) {
@@ -68,14 +82,17 @@ impl Component<BuffLogProps<'_>> for BuffLog {
                         ui.same_line();
                     }
 
-                    ui.text(apply.buff.as_ref());
+                    ui.text(buff_data.name(apply.buff));
 
                     if self.display_duration {
                         ui.same_line();
-                        ui.text_colored(
-                            yellow,
-                            format!("{}.{}s", apply.duration / 1000, apply.duration % 1000),
-                        );
+                        ui.text_colored(yellow, Self::format_duration(apply.duration));
+                    }
+
+                    if self.display_effective {
+                        let color = if apply.wasted() > 0 { red } else { green };
+                        ui.same_line();
+                        ui.text_colored(color, Self::format_duration(apply.effective));
                     }
 
                     ui.same_line();
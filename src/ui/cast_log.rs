@@ -24,7 +24,9 @@ use strum::{AsRefStr, VariantArray, VariantNames};
 pub struct CastLog {
     display_time: bool,
     display_duration: bool,
+    display_damage: bool,
     display_hits: HitDisplay,
+    display_failed: bool,
     only_misses: bool,
 
     #[serde(skip)]
@@ -36,7 +38,9 @@ impl CastLog {
         Self {
             display_time: true,
             display_duration: true,
+            display_damage: false,
             display_hits: HitDisplay::default(),
+            display_failed: true,
             only_misses: false,
             scroll: AutoScroll::new(),
         }
@@ -45,10 +49,16 @@ impl CastLog {
     pub fn render_display(&mut self, ui: &Ui) {
         ui.checkbox("Display time", &mut self.display_time);
         ui.checkbox("Display duration", &mut self.display_duration);
+        ui.checkbox("Display damage", &mut self.display_damage);
 
         ui.set_next_item_width(ch_width(ui, 16));
         enum_combo_array(ui, "Hits", &mut self.display_hits);
 
+        ui.checkbox("Display failed hits", &mut self.display_failed);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Display blocked, evaded & other failed hit attempts");
+        }
+
         ui.checkbox("Only misses", &mut self.only_misses);
     }
 
@@ -101,7 +111,7 @@ impl Component<CastLogProps<'_>> for CastLog {
                 if let Some(info) = data.get(cast.skill) {
                     if self.only_misses {
                         if let Some(hit_info) = &info.hits {
-                            if !hit_info.missed(cast.hits.len()) {
+                            if !hit_info.missed(cast.hit_count()) {
                                 continue;
                             }
                         }
@@ -116,17 +126,13 @@ impl Component<CastLogProps<'_>> for CastLog {
 
                     if let Some(hit_info) = &info.hits {
                         if let HitDisplay::Target | HitDisplay::Both = self.display_hits {
-                            let target_hits = if let Some(species) = fight.target {
-                                cast.hits.iter().filter(|hit| hit.target == species).count()
-                            } else {
-                                0
-                            };
+                            let target_hits = cast.target_hits(fight.target);
                             let (color, text) = Self::format_hits(&colors, target_hits, hit_info);
                             ui.same_line();
                             ui.text_colored(color, text);
                         }
 
-                        let cleave_hits = cast.hits.len();
+                        let cleave_hits = cast.hit_count();
                         let (color, text) = Self::format_hits(&colors, cleave_hits, hit_info);
                         match self.display_hits {
                             HitDisplay::Cleave => {
@@ -139,6 +145,24 @@ impl Component<CastLogProps<'_>> for CastLog {
                             }
                             _ => {}
                         }
+
+                        if self.display_failed {
+                            let failed = cast.failed_hits();
+                            if !failed.is_empty() {
+                                let text = failed
+                                    .iter()
+                                    .map(|(outcome, count)| format!("{count} {}", outcome.as_ref()))
+                                    .collect::<Vec<_>>()
+                                    .join(", ");
+                                ui.same_line();
+                                ui.text_colored(grey, format!("[{text}]"));
+                            }
+                        }
+                    }
+
+                    if self.display_damage {
+                        ui.same_line();
+                        ui.text(cast.damage().to_string());
                     }
 
                     if self.display_duration {
@@ -148,6 +172,7 @@ impl Component<CastLogProps<'_>> for CastLog {
                             CastState::Cancel => ui.text_colored(yellow, text),
                             CastState::Fire => ui.text_colored(green, text),
                             CastState::Interrupt => ui.text_colored(red, text),
+                            CastState::Instant => ui.text_colored(grey, "instant"),
                             CastState::Unknown | CastState::Casting | CastState::Pre => {
                                 ui.text("?ms")
                             }
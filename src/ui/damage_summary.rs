@@ -0,0 +1,136 @@
+use crate::{
+    combat::{skill::SkillMap, summary::SkillSummary, CombatData},
+    history::History,
+};
+use arc_util::{
+    colors::GREY,
+    settings::HasSettings,
+    ui::{Component, Windowable},
+};
+use arcdps::{
+    exports::{self, CoreColor},
+    imgui::Ui,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DamageSummary {
+    display_average: bool,
+    display_dps: bool,
+}
+
+impl DamageSummary {
+    pub const fn new() -> Self {
+        Self {
+            display_average: true,
+            display_dps: true,
+        }
+    }
+
+    pub fn render_display(&mut self, ui: &Ui) {
+        ui.checkbox("Display average per cast", &mut self.display_average);
+        ui.checkbox("Display damage per second", &mut self.display_dps);
+        if ui.is_item_hovered() {
+            ui.tooltip_text(
+                "Damage per second of animation time, only casts with known animation time",
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DamageSummaryProps<'a> {
+    pub skills: &'a mut SkillMap,
+    pub history: &'a mut History<CombatData>,
+}
+
+impl Component<DamageSummaryProps<'_>> for DamageSummary {
+    fn render(&mut self, ui: &Ui, props: DamageSummaryProps) {
+        let DamageSummaryProps { skills, history } = props;
+
+        if let Some(fight) = history.viewed_fight() {
+            let colors = exports::colors();
+            let grey = colors.core(CoreColor::MediumGrey).unwrap_or(GREY);
+
+            let summaries = SkillSummary::from_casts(&fight.data.casts);
+            if summaries.is_empty() {
+                ui.text_colored(grey, "No casts");
+                return;
+            }
+
+            let columns = 3 + self.display_average as usize + self.display_dps as usize;
+            if let Some(_table) = ui.begin_table("##damage", columns) {
+                ui.table_setup_column("Skill");
+                ui.table_setup_column("Casts");
+                ui.table_setup_column("Damage");
+                if self.display_average {
+                    ui.table_setup_column("Avg");
+                }
+                if self.display_dps {
+                    ui.table_setup_column("DPS");
+                }
+                ui.table_headers_row();
+
+                for summary in &summaries {
+                    ui.table_next_row();
+
+                    ui.table_next_column();
+                    ui.text(skills.get_name(summary.skill));
+
+                    ui.table_next_column();
+                    ui.text(summary.casts.to_string());
+
+                    ui.table_next_column();
+                    ui.text(summary.damage.to_string());
+
+                    if self.display_average {
+                        ui.table_next_column();
+                        ui.text(format!("{:.0}", summary.average_damage()));
+                    }
+
+                    if self.display_dps {
+                        ui.table_next_column();
+                        match summary.damage_per_second() {
+                            Some(dps) => ui.text(format!("{dps:.0}")),
+                            None => ui.text_colored(grey, "?"),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for DamageSummary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Windowable<DamageSummaryProps<'_>> for DamageSummary {
+    const CONTEXT_MENU: bool = true;
+
+    fn render_menu(&mut self, ui: &Ui, props: &mut DamageSummaryProps) {
+        ui.menu("History", || props.history.render_select(ui));
+
+        ui.spacing();
+        ui.spacing();
+
+        ui.menu("Display", || self.render_display(ui));
+    }
+}
+
+impl HasSettings for DamageSummary {
+    type Settings = Self;
+
+    const SETTINGS_ID: &'static str = "damage_summary";
+
+    fn current_settings(&self) -> Self::Settings {
+        self.clone()
+    }
+
+    fn load_settings(&mut self, loaded: Self::Settings) {
+        *self = loaded;
+    }
+}
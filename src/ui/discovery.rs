@@ -0,0 +1,159 @@
+use crate::{
+    combat::{discovery::Discovery, skill::SkillMap},
+    data::{SkillData, SkillDef},
+};
+use arc_util::{
+    colors::{GREY, RED},
+    settings::HasSettings,
+    ui::{Component, Windowable},
+};
+use arcdps::{
+    exports::{self, CoreColor},
+    imgui::Ui,
+};
+use serde::{Deserialize, Serialize};
+use std::mem;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkillDiscovery {
+    display_id: bool,
+    display_hit_counts: bool,
+
+    #[serde(skip)]
+    saves: Vec<SkillDef>,
+}
+
+impl SkillDiscovery {
+    pub const fn new() -> Self {
+        Self {
+            display_id: true,
+            display_hit_counts: true,
+            saves: Vec::new(),
+        }
+    }
+
+    /// Takes the definitions requested to be added.
+    pub fn take_saves(&mut self) -> Vec<SkillDef> {
+        mem::take(&mut self.saves)
+    }
+
+    pub fn render_display(&mut self, ui: &Ui) {
+        ui.checkbox("Display skill id", &mut self.display_id);
+        ui.checkbox("Display hit counts", &mut self.display_hit_counts);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Display number of casts per hit count");
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SkillDiscoveryProps<'a> {
+    pub skills: &'a mut SkillMap,
+    pub data: &'a SkillData,
+    pub discovery: &'a mut Discovery,
+    pub enabled: bool,
+    pub save_error: Option<&'a str>,
+}
+
+impl Component<SkillDiscoveryProps<'_>> for SkillDiscovery {
+    fn render(&mut self, ui: &Ui, props: SkillDiscoveryProps) {
+        let SkillDiscoveryProps {
+            skills,
+            data,
+            discovery,
+            enabled,
+            save_error,
+        } = props;
+
+        let colors = exports::colors();
+        let grey = colors.core(CoreColor::MediumGrey).unwrap_or(GREY);
+        let red = colors.core(CoreColor::LightRed).unwrap_or(RED);
+
+        if let Some(err) = save_error {
+            ui.text_colored(red, err);
+        }
+
+        if !enabled {
+            ui.text_colored(grey, "Discovery disabled in settings");
+        }
+
+        let mut empty = true;
+        for skill in discovery
+            .skills()
+            .iter()
+            .filter(|skill| !data.contains(skill.id))
+        {
+            empty = false;
+
+            if self.display_id {
+                ui.text_colored(grey, format!("{:>6}", skill.id));
+                ui.same_line();
+            }
+
+            ui.text(skills.get_name(skill.id));
+            ui.same_line();
+            ui.text_colored(grey, format!("{} casts {} hits", skill.casts, skill.hits));
+
+            if self.display_hit_counts && !skill.hit_counts.is_empty() {
+                let counts = skill
+                    .hit_counts
+                    .iter()
+                    .map(|(hits, casts)| format!("{hits}x{casts}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                ui.same_line();
+                ui.text_colored(grey, counts);
+            }
+
+            ui.same_line();
+            if ui.small_button(format!("Add##{}", skill.id)) {
+                let mut def = SkillDef::new(skill.id);
+                def.hits = skill.common_hits().filter(|hits| *hits > 0);
+                self.saves.push(def);
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip_text("Add starter definition to custom definitions");
+            }
+        }
+
+        if empty && enabled {
+            ui.text_colored(grey, "No undefined skills used");
+        }
+    }
+}
+
+impl Default for SkillDiscovery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Windowable<SkillDiscoveryProps<'_>> for SkillDiscovery {
+    const CONTEXT_MENU: bool = true;
+
+    fn render_menu(&mut self, ui: &Ui, props: &mut SkillDiscoveryProps) {
+        if ui.menu_item("Clear") {
+            props.discovery.clear();
+        }
+
+        ui.spacing();
+        ui.spacing();
+
+        ui.menu("Display", || self.render_display(ui));
+    }
+}
+
+impl HasSettings for SkillDiscovery {
+    type Settings = Self;
+
+    const SETTINGS_ID: &'static str = "discovery";
+
+    fn current_settings(&self) -> Self::Settings {
+        self.clone()
+    }
+
+    fn load_settings(&mut self, loaded: Self::Settings) {
+        *self = loaded;
+    }
+}
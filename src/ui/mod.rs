@@ -1,9 +1,13 @@
 pub mod breakbar_log;
 pub mod buff_log;
 pub mod cast_log;
+pub mod damage_summary;
+pub mod discovery;
 pub mod multi_view;
 pub mod scroll;
 pub mod transfer_log;
+pub mod tuner;
+pub mod uptime;
 
 // TODO: generic log component for reuse?
 
@@ -2,11 +2,13 @@ use super::{
     breakbar_log::{BreakbarLog, BreakbarLogProps},
     buff_log::{BuffLog, BuffLogProps},
     cast_log::{CastLog, CastLogProps},
+    damage_summary::{DamageSummary, DamageSummaryProps},
     transfer_log::{TransferLog, TransferLogProps},
+    uptime::{UptimeTable, UptimeTableProps},
 };
 use crate::{
     combat::{skill::SkillMap, CombatData},
-    data::SkillData,
+    data::{BuffData, SkillData},
     history::History,
 };
 use arc_util::{
@@ -22,6 +24,8 @@ pub struct MultiView {
     pub buffs: BuffLog,
     pub breakbars: BreakbarLog,
     pub transfers: TransferLog,
+    pub damage: DamageSummary,
+    pub uptime: UptimeTable,
 }
 
 impl MultiView {
@@ -31,6 +35,8 @@ impl MultiView {
             buffs: BuffLog::new(),
             breakbars: BreakbarLog::new(),
             transfers: TransferLog::new(),
+            damage: DamageSummary::new(),
+            uptime: UptimeTable::new(),
         }
     }
 
@@ -43,6 +49,7 @@ impl MultiView {
 pub struct MultiViewProps<'a> {
     pub skills: &'a mut SkillMap,
     pub data: &'a SkillData,
+    pub buff_data: &'a BuffData,
     pub history: &'a mut History<CombatData>,
 }
 
@@ -51,6 +58,7 @@ impl Component<MultiViewProps<'_>> for MultiView {
         let MultiViewProps {
             skills,
             data,
+            buff_data,
             history,
         } = props;
 
@@ -66,7 +74,7 @@ impl Component<MultiViewProps<'_>> for MultiView {
                 )
             });
             Self::scroll_tab(ui, "Buffs", || {
-                self.buffs.render(ui, BuffLogProps { history })
+                self.buffs.render(ui, BuffLogProps { buff_data, history })
             });
             Self::scroll_tab(ui, "Breakbar", || {
                 self.breakbars
@@ -75,6 +83,14 @@ impl Component<MultiViewProps<'_>> for MultiView {
             Self::scroll_tab(ui, "Transfer", || {
                 self.transfers.render(ui, TransferLogProps { history })
             });
+            Self::scroll_tab(ui, "Damage", || {
+                self.damage
+                    .render(ui, DamageSummaryProps { skills, history })
+            });
+            Self::scroll_tab(ui, "Uptime", || {
+                self.uptime
+                    .render(ui, UptimeTableProps { buff_data, history })
+            });
         });
     }
 }
@@ -98,6 +114,8 @@ impl Windowable<MultiViewProps<'_>> for MultiView {
         ui.menu("Buffs Display", || self.buffs.render_display(ui));
         ui.menu("Breakbar Display", || self.breakbars.render_display(ui));
         ui.menu("Transfer Display", || self.transfers.render_display(ui));
+        ui.menu("Damage Display", || self.damage.render_display(ui));
+        ui.menu("Uptime Display", || self.uptime.render_display(ui));
     }
 }
 
@@ -108,6 +126,8 @@ pub struct MultiViewSettings {
     pub buffs: <BuffLog as HasSettings>::Settings,
     pub breakbars: <BreakbarLog as HasSettings>::Settings,
     pub transfers: <TransferLog as HasSettings>::Settings,
+    pub damage: <DamageSummary as HasSettings>::Settings,
+    pub uptime: <UptimeTable as HasSettings>::Settings,
 }
 
 impl HasSettings for MultiView {
@@ -121,6 +141,8 @@ impl HasSettings for MultiView {
             buffs: self.buffs.current_settings(),
             breakbars: self.breakbars.current_settings(),
             transfers: self.transfers.current_settings(),
+            damage: self.damage.current_settings(),
+            uptime: self.uptime.current_settings(),
         }
     }
 
@@ -130,10 +152,14 @@ impl HasSettings for MultiView {
             buffs,
             breakbars,
             transfers,
+            damage,
+            uptime,
         } = loaded;
         self.casts.load_settings(casts);
         self.buffs.load_settings(buffs);
         self.breakbars.load_settings(breakbars);
         self.transfers.load_settings(transfers);
+        self.damage.load_settings(damage);
+        self.uptime.load_settings(uptime);
     }
 }
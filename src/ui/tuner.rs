@@ -0,0 +1,230 @@
+use crate::{
+    combat::{
+        skill::SkillMap,
+        tuner::{HitStats, Suggestion},
+        CombatData,
+    },
+    data::{SkillData, SkillDef},
+    history::History,
+};
+use arc_util::{
+    colors::{GREEN, GREY, RED},
+    settings::HasSettings,
+    ui::{Component, Windowable},
+};
+use arcdps::{
+    exports::{self, CoreColor},
+    imgui::Ui,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, mem};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DefinitionTuner {
+    min_casts: usize,
+    display_distribution: bool,
+
+    #[serde(skip)]
+    saves: Vec<SkillDef>,
+
+    /// Stats cached for a history revision.
+    #[serde(skip)]
+    stats: Option<(u64, Vec<HitStats>)>,
+}
+
+impl DefinitionTuner {
+    pub const fn new() -> Self {
+        Self {
+            min_casts: 5,
+            display_distribution: true,
+            saves: Vec::new(),
+            stats: None,
+        }
+    }
+
+    /// Takes the definitions requested to be saved as overrides.
+    pub fn take_saves(&mut self) -> Vec<SkillDef> {
+        mem::take(&mut self.saves)
+    }
+
+    pub fn render_display(&mut self, ui: &Ui) {
+        let mut min_casts = self.min_casts as _;
+        ui.set_next_item_width(100.0);
+        if ui
+            .input_int("Min casts", &mut min_casts)
+            .step(1)
+            .step_fast(10)
+            .build()
+        {
+            self.min_casts = min_casts.try_into().unwrap_or_default();
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Minimum amount of casts with hits to suggest values");
+        }
+
+        ui.checkbox("Display distribution", &mut self.display_distribution);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Display number of casts per hit count");
+        }
+    }
+
+    fn format_distribution(dist: &BTreeMap<usize, usize>) -> String {
+        dist.iter()
+            .map(|(hits, casts)| format!("{hits}x{casts}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn format_values(
+        hits: Option<usize>,
+        expected: Option<usize>,
+        duration: Option<i32>,
+    ) -> String {
+        let format = |value: Option<String>| value.unwrap_or_else(|| "-".into());
+        format!(
+            "{}/{} {}",
+            format(expected.map(|value| value.to_string())),
+            format(hits.map(|value| value.to_string())),
+            format(duration.map(|value| format!("{value}ms"))),
+        )
+    }
+
+    /// Creates an override definition with the suggested values.
+    fn apply_suggestion(def: Option<&SkillDef>, skill: u32, suggestion: Suggestion) -> SkillDef {
+        let mut def = def.cloned().unwrap_or_else(|| SkillDef::new(skill));
+        def.hits = Some(suggestion.hits);
+        def.expected = Some(suggestion.expected);
+        def.max_duration = suggestion.max_duration.or(def.max_duration);
+        def
+    }
+}
+
+#[derive(Debug)]
+pub struct DefinitionTunerProps<'a> {
+    pub skills: &'a mut SkillMap,
+    pub data: &'a SkillData,
+    pub history: &'a mut History<CombatData>,
+    pub save_error: Option<&'a str>,
+}
+
+impl Component<DefinitionTunerProps<'_>> for DefinitionTuner {
+    fn render(&mut self, ui: &Ui, props: DefinitionTunerProps) {
+        let DefinitionTunerProps {
+            skills,
+            data,
+            history,
+            save_error,
+        } = props;
+
+        let colors = exports::colors();
+        let grey = colors.core(CoreColor::MediumGrey).unwrap_or(GREY);
+        let green = colors.core(CoreColor::LightGreen).unwrap_or(GREEN);
+        let red = colors.core(CoreColor::LightRed).unwrap_or(RED);
+
+        if let Some(err) = save_error {
+            ui.text_colored(red, err);
+        }
+
+        // casts only change with the history, including re-evaluation for new skill data
+        let revision = history.revision();
+        let (_, stats) = match &mut self.stats {
+            Some(cached) if cached.0 == revision => cached,
+            stats => stats.insert((revision, HitStats::from_fights(history.all_fights()))),
+        };
+        let stats = stats
+            .iter()
+            .filter(|stats| stats.casts >= self.min_casts)
+            .collect::<Vec<_>>();
+        if stats.is_empty() {
+            ui.text_colored(grey, "Not enough casts");
+            return;
+        }
+
+        let columns = 4 + self.display_distribution as usize;
+        if let Some(_table) = ui.begin_table("##tuner", columns) {
+            ui.table_setup_column("Skill");
+            ui.table_setup_column("Casts");
+            if self.display_distribution {
+                ui.table_setup_column("Hits");
+            }
+            ui.table_setup_column("Current");
+            ui.table_setup_column("Suggested");
+            ui.table_headers_row();
+
+            for stats in stats {
+                let def = data.get_def(stats.skill);
+                let suggestion = stats.suggest();
+
+                ui.table_next_row();
+
+                ui.table_next_column();
+                ui.text(skills.get_name(stats.skill));
+
+                ui.table_next_column();
+                ui.text(stats.casts.to_string());
+
+                if self.display_distribution {
+                    ui.table_next_column();
+                    ui.text(Self::format_distribution(stats.distribution()));
+                }
+
+                ui.table_next_column();
+                match def {
+                    Some(def) => ui.text(Self::format_values(
+                        def.hits,
+                        def.expected,
+                        def.max_duration,
+                    )),
+                    None => ui.text_colored(grey, "-"),
+                }
+
+                ui.table_next_column();
+                ui.text_colored(
+                    green,
+                    Self::format_values(
+                        Some(suggestion.hits),
+                        Some(suggestion.expected),
+                        suggestion.max_duration,
+                    ),
+                );
+                ui.same_line();
+                if ui.small_button(format!("Save##{}", stats.skill)) {
+                    self.saves
+                        .push(Self::apply_suggestion(def, stats.skill, suggestion));
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Save suggested values as override to custom definitions");
+                }
+            }
+        }
+    }
+}
+
+impl Default for DefinitionTuner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Windowable<DefinitionTunerProps<'_>> for DefinitionTuner {
+    const CONTEXT_MENU: bool = true;
+
+    fn render_menu(&mut self, ui: &Ui, _props: &mut DefinitionTunerProps) {
+        ui.menu("Display", || self.render_display(ui));
+    }
+}
+
+impl HasSettings for DefinitionTuner {
+    type Settings = Self;
+
+    const SETTINGS_ID: &'static str = "definition_tuner";
+
+    fn current_settings(&self) -> Self::Settings {
+        self.clone()
+    }
+
+    fn load_settings(&mut self, loaded: Self::Settings) {
+        *self = loaded;
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{combat::CombatData, data::BuffData, history::History};
+use arc_util::{
+    colors::GREY,
+    settings::HasSettings,
+    ui::{Component, Windowable},
+};
+use arcdps::{
+    exports::{self, CoreColor},
+    imgui::Ui,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UptimeTable {
+    display_sources: bool,
+}
+
+impl UptimeTable {
+    /// Maximum number of table columns supported by imgui.
+    const MAX_COLUMNS: usize = 64;
+
+    pub const fn new() -> Self {
+        Self {
+            display_sources: true,
+        }
+    }
+
+    pub fn render_display(&mut self, ui: &Ui) {
+        ui.checkbox("Display sources", &mut self.display_sources);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Display uptime from own & other sources");
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UptimeTableProps<'a> {
+    pub buff_data: &'a BuffData,
+    pub history: &'a mut History<CombatData>,
+}
+
+impl Component<UptimeTableProps<'_>> for UptimeTable {
+    fn render(&mut self, ui: &Ui, props: UptimeTableProps) {
+        let UptimeTableProps { buff_data, history } = props;
+
+        if let Some(fight) = history.viewed_fight() {
+            let colors = exports::colors();
+            let grey = colors.core(CoreColor::MediumGrey).unwrap_or(GREY);
+
+            let uptime = &fight.data.uptime;
+            let mut buffs = uptime.buffs();
+            buffs.sort_by_key(|buff| buff_data.sort_key(*buff));
+            if buffs.is_empty() {
+                ui.text_colored(grey, "No uptime");
+                return;
+            }
+            let end = fight.end.unwrap_or(uptime.updated);
+            let duration = end.saturating_sub(fight.start);
+
+            // one column is used for the player
+            let hidden = buffs.len().saturating_sub(Self::MAX_COLUMNS - 1);
+            if hidden > 0 {
+                buffs.truncate(Self::MAX_COLUMNS - 1);
+                ui.text_colored(grey, format!("{hidden} buffs hidden"));
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Disable buffs to show the remaining ones");
+                }
+            }
+
+            if let Some(_table) = ui.begin_table("##uptime", buffs.len() + 1) {
+                ui.table_setup_column("Player");
+                for buff in &buffs {
+                    ui.table_setup_column(buff_data.name(*buff));
+                }
+                ui.table_headers_row();
+
+                for player in &uptime.players {
+                    ui.table_next_row();
+
+                    ui.table_next_column();
+                    ui.text_colored(player.player.prof_color(&colors), &player.player.name);
+
+                    for buff in &buffs {
+                        ui.table_next_column();
+                        match player.get(*buff) {
+                            Some(buff_uptime) => {
+                                let (total, own, others) = buff_uptime.percentages(duration);
+                                ui.text(format!("{total:.0}%"));
+                                if self.display_sources {
+                                    ui.same_line();
+                                    ui.text_colored(grey, format!("({own:.0}/{others:.0})"));
+                                }
+                                if ui.is_item_hovered() {
+                                    ui.tooltip_text(format!("{own:.1}% own\n{others:.1}% others"));
+                                }
+                            }
+                            None => ui.text_colored(grey, "-"),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for UptimeTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Windowable<UptimeTableProps<'_>> for UptimeTable {
+    const CONTEXT_MENU: bool = true;
+
+    fn render_menu(&mut self, ui: &Ui, props: &mut UptimeTableProps) {
+        ui.menu("History", || props.history.render_select(ui));
+
+        ui.spacing();
+        ui.spacing();
+
+        ui.menu("Display", || self.render_display(ui));
+    }
+}
+
+impl HasSettings for UptimeTable {
+    type Settings = Self;
+
+    const SETTINGS_ID: &'static str = "uptime_table";
+
+    fn current_settings(&self) -> Self::Settings {
+        self.clone()
+    }
+
+    fn load_settings(&mut self, loaded: Self::Settings) {
+        *self = loaded;
+    }
+}